@@ -0,0 +1,118 @@
+//! Derive macros for the `anthropic` crate.
+//!
+//! Enable them through the `macros` feature of `anthropic` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Expr, ExprLit, Lit, LitStr, Meta};
+
+/// Derive `anthropic::tools::ToolDefinition` for a tool input type.
+///
+/// The tool name defaults to the type name in snake_case and the description to the
+/// type's doc comment. Both can be overridden:
+///
+/// ```ignore
+/// /// Get the current weather for a location.
+/// #[derive(serde::Deserialize, schemars::JsonSchema, AnthropicTool)]
+/// #[tool(name = "get_weather")]
+/// struct GetWeather {
+///     location: String,
+/// }
+/// ```
+#[proc_macro_derive(AnthropicTool, attributes(tool))]
+pub fn derive_anthropic_tool(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut name = None;
+    let mut description = None;
+    let mut docs = Vec::new();
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("tool") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("description") {
+                    description = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `name` or `description`"))
+                }
+            })?;
+        } else if attr.path().is_ident("doc") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }) = &meta.value {
+                    docs.push(doc.value().trim().to_string());
+                }
+            }
+        }
+    }
+
+    let name = name.unwrap_or_else(|| to_snake_case(&input.ident.to_string()));
+    let description = match description {
+        Some(description) => description,
+        None if !docs.is_empty() => join_docs(&docs),
+        None => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "tools need a description: add a doc comment or #[tool(description = \"...\")]",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::anthropic::tools::ToolDefinition for #ident #ty_generics #where_clause {
+            type Input = Self;
+
+            fn name() -> &'static str {
+                #name
+            }
+
+            fn description() -> &'static str {
+                #description
+            }
+        }
+    })
+}
+
+/// Join doc comment lines, unwrapping lines within a paragraph.
+fn join_docs(docs: &[String]) -> String {
+    let mut joined = String::new();
+    for line in docs {
+        if line.is_empty() {
+            if !joined.is_empty() && !joined.ends_with("\n\n") {
+                joined.push_str("\n\n");
+            }
+        } else {
+            if !joined.is_empty() && !joined.ends_with('\n') {
+                joined.push(' ');
+            }
+            joined.push_str(line);
+        }
+    }
+    joined.trim_end().to_string()
+}
+
+fn to_snake_case(ident: &str) -> String {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut snake = String::with_capacity(ident.len() + 4);
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && index > 0 {
+            // Runs of capitals are one word: `GetHTTPWeather` becomes `get_http_weather`.
+            let previous = chars[index - 1];
+            let next_is_lowercase = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if !previous.is_uppercase() || next_is_lowercase {
+                snake.push('_');
+            }
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    snake
+}
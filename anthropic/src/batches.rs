@@ -0,0 +1,187 @@
+//! Types and client methods for the Message Batches API.
+
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use tokio_stream::Stream;
+
+use crate::client::{jsonl_stream, Client};
+use crate::error::{AnthropicError, ErrorResponse};
+use crate::response::Response;
+use crate::types::{ListParams, MessagesRequest, MessagesResponse, Page};
+
+/// A single request inside a message batch.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct BatchRequest {
+    pub custom_id: String,
+    pub params: MessagesRequest,
+}
+
+impl BatchRequest {
+    pub fn new(custom_id: impl Into<String>, params: MessagesRequest) -> Self {
+        Self { custom_id: custom_id.into(), params }
+    }
+}
+
+#[derive(Serialize)]
+struct CreateMessageBatchRequest {
+    requests: Vec<BatchRequest>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStatus {
+    InProgress,
+    Canceling,
+    Ended,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct RequestCounts {
+    #[serde(default)]
+    pub processing: u32,
+    #[serde(default)]
+    pub succeeded: u32,
+    #[serde(default)]
+    pub errored: u32,
+    #[serde(default)]
+    pub canceled: u32,
+    #[serde(default)]
+    pub expired: u32,
+}
+
+/// A message batch and its processing state.
+///
+/// Timestamps are RFC 3339 strings as returned by the API.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MessageBatch {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub batch_type: String,
+    pub processing_status: ProcessingStatus,
+    pub request_counts: RequestCounts,
+    pub created_at: String,
+    pub expires_at: String,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    #[serde(default)]
+    pub cancel_initiated_at: Option<String>,
+    #[serde(default)]
+    pub results_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeletedMessageBatch {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub deleted_type: String,
+}
+
+/// Outcome of a single request in a finished batch.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum BatchResult {
+    Succeeded { message: MessagesResponse },
+    Errored { error: ErrorResponse },
+    Canceled,
+    Expired,
+}
+
+/// One line of a batch results file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MessageBatchResult {
+    pub custom_id: String,
+    pub result: BatchResult,
+}
+
+pub type MessageBatchResultStream = Pin<Box<dyn Stream<Item = Result<MessageBatchResult, AnthropicError>> + Send>>;
+
+impl Client {
+    pub async fn create_message_batch(&self, requests: Vec<BatchRequest>) -> Result<MessageBatch, AnthropicError> {
+        self.create_message_batch_with_response(requests).await.map(Response::into_body)
+    }
+
+    pub async fn create_message_batch_with_response(
+        &self,
+        requests: Vec<BatchRequest>,
+    ) -> Result<Response<MessageBatch>, AnthropicError> {
+        if requests.iter().any(|request| matches!(request.params.stream, Some(true))) {
+            return Err(AnthropicError::InvalidRequest("batch requests cannot set stream=true".into()));
+        }
+        for request in &requests {
+            request.params.validate_cache_breakpoints().map_err(|err| match err {
+                AnthropicError::InvalidRequest(message) => {
+                    AnthropicError::InvalidRequest(format!("batch request {}: {message}", request.custom_id))
+                }
+                err => err,
+            })?;
+        }
+        let mut betas = Vec::new();
+        for beta in requests.iter().flat_map(|request| request.params.required_betas()) {
+            if !betas.contains(&beta) {
+                betas.push(beta);
+            }
+        }
+        self.post_with_betas("/v1/messages/batches", &CreateMessageBatchRequest { requests }, &betas).await
+    }
+
+    pub async fn retrieve_message_batch(&self, batch_id: &str) -> Result<MessageBatch, AnthropicError> {
+        self.retrieve_message_batch_with_response(batch_id).await.map(Response::into_body)
+    }
+
+    pub async fn retrieve_message_batch_with_response(
+        &self,
+        batch_id: &str,
+    ) -> Result<Response<MessageBatch>, AnthropicError> {
+        self.get(&format!("/v1/messages/batches/{batch_id}")).await
+    }
+
+    pub async fn list_message_batches(&self, params: &ListParams) -> Result<Page<MessageBatch>, AnthropicError> {
+        self.list_message_batches_with_response(params).await.map(Response::into_body)
+    }
+
+    pub async fn list_message_batches_with_response(
+        &self,
+        params: &ListParams,
+    ) -> Result<Response<Page<MessageBatch>>, AnthropicError> {
+        self.get_with_query("/v1/messages/batches", params).await
+    }
+
+    pub async fn cancel_message_batch(&self, batch_id: &str) -> Result<MessageBatch, AnthropicError> {
+        self.cancel_message_batch_with_response(batch_id).await.map(Response::into_body)
+    }
+
+    pub async fn cancel_message_batch_with_response(
+        &self,
+        batch_id: &str,
+    ) -> Result<Response<MessageBatch>, AnthropicError> {
+        self.post(&format!("/v1/messages/batches/{batch_id}/cancel"), &serde_json::json!({})).await
+    }
+
+    pub async fn delete_message_batch(&self, batch_id: &str) -> Result<DeletedMessageBatch, AnthropicError> {
+        self.delete_message_batch_with_response(batch_id).await.map(Response::into_body)
+    }
+
+    pub async fn delete_message_batch_with_response(
+        &self,
+        batch_id: &str,
+    ) -> Result<Response<DeletedMessageBatch>, AnthropicError> {
+        self.delete(&format!("/v1/messages/batches/{batch_id}")).await
+    }
+
+    /// Stream the results of an ended batch, decoding the JSONL file line by line.
+    pub async fn message_batch_results(&self, batch_id: &str) -> Result<MessageBatchResultStream, AnthropicError> {
+        self.message_batch_results_with_response(batch_id).await.map(Response::into_body)
+    }
+
+    /// Like [`Client::message_batch_results`], with the response headers; `elapsed` stops at the headers.
+    pub async fn message_batch_results_with_response(
+        &self,
+        batch_id: &str,
+    ) -> Result<Response<MessageBatchResultStream>, AnthropicError> {
+        let response = self.get_raw(&format!("/v1/messages/batches/{batch_id}/results")).await?;
+        Ok(response.map(jsonl_stream))
+    }
+}
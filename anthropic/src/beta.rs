@@ -0,0 +1,179 @@
+//! Beta feature flags sent in the `anthropic-beta` header.
+
+use std::fmt;
+
+use crate::types::{
+    ClientTool, ContentBlock, CountTokensRequest, DocumentSource, ImageSource, McpServer, Message, MessagesRequest,
+    ServerTool, SystemPrompt, ToolUnion, Ttl,
+};
+
+pub(crate) const BETA_HEADER: &str = "anthropic-beta";
+
+/// A beta feature flag; unknown flags are passed through as [`Beta::Custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Beta {
+    ExtendedCacheTtl,
+    FilesApi,
+    McpClient,
+    ContextManagement,
+    CodeExecution20250522,
+    CodeExecution20250825,
+    WebFetch,
+    ComputerUse20241022,
+    ComputerUse20250124,
+    InterleavedThinking,
+    FineGrainedToolStreaming,
+    TokenEfficientTools,
+    Output128k,
+    Context1m,
+    Custom(String),
+}
+
+impl Beta {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Beta::ExtendedCacheTtl => "extended-cache-ttl-2025-04-11",
+            Beta::FilesApi => "files-api-2025-04-14",
+            Beta::McpClient => "mcp-client-2025-04-04",
+            Beta::ContextManagement => "context-management-2025-06-27",
+            Beta::CodeExecution20250522 => "code-execution-2025-05-22",
+            Beta::CodeExecution20250825 => "code-execution-2025-08-25",
+            Beta::WebFetch => "web-fetch-2025-09-10",
+            Beta::ComputerUse20241022 => "computer-use-2024-10-22",
+            Beta::ComputerUse20250124 => "computer-use-2025-01-24",
+            Beta::InterleavedThinking => "interleaved-thinking-2025-05-14",
+            Beta::FineGrainedToolStreaming => "fine-grained-tool-streaming-2025-05-14",
+            Beta::TokenEfficientTools => "token-efficient-tools-2025-02-19",
+            Beta::Output128k => "output-128k-2025-02-19",
+            Beta::Context1m => "context-1m-2025-08-07",
+            Beta::Custom(beta) => beta,
+        }
+    }
+
+    /// Parse a comma-separated `anthropic-beta` value such as the `ANTHROPIC_BETA` variable.
+    pub fn parse_list(value: &str) -> Vec<Beta> {
+        value.split(',').map(str::trim).filter(|beta| !beta.is_empty()).map(Beta::from).collect()
+    }
+}
+
+impl From<&str> for Beta {
+    fn from(beta: &str) -> Self {
+        match beta {
+            "extended-cache-ttl-2025-04-11" => Beta::ExtendedCacheTtl,
+            "files-api-2025-04-14" => Beta::FilesApi,
+            "mcp-client-2025-04-04" => Beta::McpClient,
+            "context-management-2025-06-27" => Beta::ContextManagement,
+            "code-execution-2025-05-22" => Beta::CodeExecution20250522,
+            "code-execution-2025-08-25" => Beta::CodeExecution20250825,
+            "web-fetch-2025-09-10" => Beta::WebFetch,
+            "computer-use-2024-10-22" => Beta::ComputerUse20241022,
+            "computer-use-2025-01-24" => Beta::ComputerUse20250124,
+            "interleaved-thinking-2025-05-14" => Beta::InterleavedThinking,
+            "fine-grained-tool-streaming-2025-05-14" => Beta::FineGrainedToolStreaming,
+            "token-efficient-tools-2025-02-19" => Beta::TokenEfficientTools,
+            "output-128k-2025-02-19" => Beta::Output128k,
+            "context-1m-2025-08-07" => Beta::Context1m,
+            _ => Beta::Custom(beta.to_string()),
+        }
+    }
+}
+
+impl From<String> for Beta {
+    fn from(beta: String) -> Self {
+        match Beta::from(beta.as_str()) {
+            Beta::Custom(_) => Beta::Custom(beta),
+            known => known,
+        }
+    }
+}
+
+impl fmt::Display for Beta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl MessagesRequest {
+    /// Beta flags the features used by this request depend on.
+    ///
+    /// The client adds these to the `anthropic-beta` header automatically.
+    pub fn required_betas(&self) -> Vec<Beta> {
+        required_betas(
+            self.system.as_ref(),
+            &self.messages,
+            self.tools.as_deref(),
+            self.mcp_servers.as_deref(),
+            self.context_management.is_some(),
+        )
+    }
+}
+
+impl CountTokensRequest {
+    /// Beta flags the features used by this request depend on.
+    pub fn required_betas(&self) -> Vec<Beta> {
+        required_betas(
+            self.system.as_ref(),
+            &self.messages,
+            self.tools.as_deref(),
+            self.mcp_servers.as_deref(),
+            self.context_management.is_some(),
+        )
+    }
+}
+
+fn required_betas(
+    system: Option<&SystemPrompt>,
+    messages: &[Message],
+    tools: Option<&[ToolUnion]>,
+    mcp_servers: Option<&[McpServer]>,
+    context_management: bool,
+) -> Vec<Beta> {
+    let mut betas = Vec::new();
+
+    for tool in tools.unwrap_or_default() {
+        if tool.cache_control().is_some_and(|cache_control| cache_control.ttl() == Ttl::OneHour) {
+            push(&mut betas, Beta::ExtendedCacheTtl);
+        }
+        match tool {
+            ToolUnion::Server(ServerTool::WebFetch20250910(_)) => push(&mut betas, Beta::WebFetch),
+            ToolUnion::Server(ServerTool::CodeExecution20250522(_)) => push(&mut betas, Beta::CodeExecution20250522),
+            ToolUnion::Server(ServerTool::CodeExecution20250825(_)) => push(&mut betas, Beta::CodeExecution20250825),
+            ToolUnion::Client(ClientTool::Computer20241022(_)) => push(&mut betas, Beta::ComputerUse20241022),
+            ToolUnion::Client(ClientTool::Computer20250124(_)) => push(&mut betas, Beta::ComputerUse20250124),
+            _ => (),
+        }
+    }
+
+    if let Some(SystemPrompt::Blocks(blocks)) = system {
+        blocks.iter().for_each(|block| block_betas(block, &mut betas));
+    }
+    for message in messages {
+        message.content.iter().for_each(|block| block_betas(block, &mut betas));
+    }
+
+    if mcp_servers.is_some_and(|servers| !servers.is_empty()) {
+        push(&mut betas, Beta::McpClient);
+    }
+    if context_management {
+        push(&mut betas, Beta::ContextManagement);
+    }
+    betas
+}
+
+fn block_betas(block: &ContentBlock, betas: &mut Vec<Beta>) {
+    if block.cache_control().is_some_and(|cache_control| cache_control.ttl() == Ttl::OneHour) {
+        push(betas, Beta::ExtendedCacheTtl);
+    }
+    if let ContentBlock::Image { source: ImageSource::File { .. }, .. }
+    | ContentBlock::Document { source: DocumentSource::File { .. }, .. } = block
+    {
+        push(betas, Beta::FilesApi);
+    }
+    block.nested_content().iter().for_each(|block| block_betas(block, betas));
+}
+
+fn push(betas: &mut Vec<Beta>, beta: Beta) {
+    if !betas.contains(&beta) {
+        betas.push(beta);
+    }
+}
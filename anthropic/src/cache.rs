@@ -0,0 +1,202 @@
+//! Placement and validation of prompt cache breakpoints.
+
+use crate::error::AnthropicError;
+use crate::types::{
+    CacheControl, ContentBlock, CountTokensRequest, Message, MessagesRequest, Role, SystemPrompt, ToolUnion, Ttl,
+};
+
+/// Maximum number of `cache_control` breakpoints accepted in a single request.
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+/// How [`MessagesRequestBuilder`](crate::types::MessagesRequestBuilder) places cache breakpoints.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum CacheStrategy {
+    /// Only the breakpoints set explicitly on blocks and tools are sent.
+    #[default]
+    Manual,
+    /// Place breakpoints at the end of the tools, the end of the system prompt and on the
+    /// latest user turns, without exceeding [`MAX_CACHE_BREAKPOINTS`].
+    Auto(Ttl),
+}
+
+/// Location of a cache breakpoint within a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheBreakpoint {
+    Tool {
+        index: usize,
+    },
+    System {
+        index: usize,
+    },
+    Message {
+        message: usize,
+        block: usize,
+    },
+    /// A block inside the content of a tool result, search result or document of a message;
+    /// `path` holds its index at each level of nesting below `block`.
+    Nested {
+        message: usize,
+        block: usize,
+        path: Vec<usize>,
+    },
+}
+
+impl MessagesRequest {
+    /// Every cache breakpoint in the request, in prompt order with its TTL.
+    pub fn cache_breakpoints(&self) -> Vec<(CacheBreakpoint, Ttl)> {
+        cache_breakpoints(self.tools.as_deref(), self.system.as_ref(), &self.messages)
+    }
+
+    /// Reject requests with more than [`MAX_CACHE_BREAKPOINTS`] breakpoints, or with a
+    /// 1 hour breakpoint after a 5 minute one.
+    pub fn validate_cache_breakpoints(&self) -> Result<(), AnthropicError> {
+        validate(self.cache_breakpoints())
+    }
+
+    /// Place cache breakpoints at the end of the tools, the end of the system prompt, the last
+    /// user turn and the user turn before it, in that priority, keeping existing breakpoints.
+    ///
+    /// Returns the breakpoints that were added.
+    pub fn place_cache_breakpoints(&mut self, ttl: Ttl) -> Result<Vec<CacheBreakpoint>, AnthropicError> {
+        let mut budget = MAX_CACHE_BREAKPOINTS.saturating_sub(self.cache_breakpoints().len());
+        let mut placed = Vec::new();
+
+        if let Some((index, tool)) = self.tools.iter_mut().flatten().enumerate().last() {
+            let cache_control = tool.cache_control_mut();
+            if budget > 0 && cache_control.is_none() {
+                *cache_control = Some(CacheControl::ephemeral(ttl));
+                placed.push(CacheBreakpoint::Tool { index });
+                budget -= 1;
+            }
+        }
+
+        if let Some(system) = self.system.take() {
+            let mut blocks = match system {
+                SystemPrompt::Text(text) => vec![ContentBlock::text(text)],
+                SystemPrompt::Blocks(blocks) => blocks,
+            };
+            if budget > 0 {
+                if let Some(index) = mark_last_cacheable(&mut blocks, ttl) {
+                    placed.push(CacheBreakpoint::System { index });
+                    budget -= 1;
+                }
+            }
+            self.system = Some(SystemPrompt::Blocks(blocks));
+        }
+
+        let user_turns: Vec<usize> = self
+            .messages
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, message)| message.role == Role::User)
+            .map(|(index, _)| index)
+            .take(2)
+            .collect();
+        for message in user_turns {
+            if budget == 0 {
+                break;
+            }
+            if let Some(block) = mark_last_cacheable(&mut self.messages[message].content, ttl) {
+                placed.push(CacheBreakpoint::Message { message, block });
+                budget -= 1;
+            }
+        }
+
+        self.validate_cache_breakpoints()?;
+        Ok(placed)
+    }
+}
+
+impl CountTokensRequest {
+    /// Every cache breakpoint in the request, in prompt order with its TTL.
+    pub fn cache_breakpoints(&self) -> Vec<(CacheBreakpoint, Ttl)> {
+        cache_breakpoints(self.tools.as_deref(), self.system.as_ref(), &self.messages)
+    }
+
+    /// Same checks as [`MessagesRequest::validate_cache_breakpoints`].
+    pub fn validate_cache_breakpoints(&self) -> Result<(), AnthropicError> {
+        validate(self.cache_breakpoints())
+    }
+}
+
+fn cache_breakpoints(
+    tools: Option<&[ToolUnion]>,
+    system: Option<&SystemPrompt>,
+    messages: &[Message],
+) -> Vec<(CacheBreakpoint, Ttl)> {
+    let mut breakpoints = Vec::new();
+
+    for (index, tool) in tools.into_iter().flatten().enumerate() {
+        if let Some(cache_control) = tool.cache_control() {
+            breakpoints.push((CacheBreakpoint::Tool { index }, cache_control.ttl()));
+        }
+    }
+    if let Some(SystemPrompt::Blocks(blocks)) = system {
+        for (index, block) in blocks.iter().enumerate() {
+            if let Some(cache_control) = block.cache_control() {
+                breakpoints.push((CacheBreakpoint::System { index }, cache_control.ttl()));
+            }
+        }
+    }
+    for (message, content) in messages.iter().enumerate() {
+        for (block, content_block) in content.content.iter().enumerate() {
+            // Nested breakpoints come first: the enclosing block's own one marks its end.
+            let mut nested = Vec::new();
+            nested_breakpoints(content_block, &mut Vec::new(), &mut nested);
+            for (path, ttl) in nested {
+                breakpoints.push((CacheBreakpoint::Nested { message, block, path }, ttl));
+            }
+            if let Some(cache_control) = content_block.cache_control() {
+                breakpoints.push((CacheBreakpoint::Message { message, block }, cache_control.ttl()));
+            }
+        }
+    }
+
+    breakpoints
+}
+
+fn validate(breakpoints: Vec<(CacheBreakpoint, Ttl)>) -> Result<(), AnthropicError> {
+    if breakpoints.len() > MAX_CACHE_BREAKPOINTS {
+        return Err(AnthropicError::InvalidRequest(format!(
+            "request has {} cache breakpoints, at most {MAX_CACHE_BREAKPOINTS} are allowed",
+            breakpoints.len()
+        )));
+    }
+    let mut seen_short = false;
+    for (breakpoint, ttl) in breakpoints {
+        match ttl {
+            Ttl::FiveMinutes => seen_short = true,
+            Ttl::OneHour if seen_short => {
+                return Err(AnthropicError::InvalidRequest(format!(
+                    "1h cache breakpoint at {breakpoint:?} must not follow a 5m breakpoint"
+                )));
+            }
+            Ttl::OneHour => (),
+        }
+    }
+    Ok(())
+}
+
+/// Collect the breakpoints nested in `block` in prompt order, with their index path below it.
+fn nested_breakpoints(block: &ContentBlock, path: &mut Vec<usize>, breakpoints: &mut Vec<(Vec<usize>, Ttl)>) {
+    for (index, nested) in block.nested_content().iter().enumerate() {
+        path.push(index);
+        nested_breakpoints(nested, path, breakpoints);
+        if let Some(cache_control) = nested.cache_control() {
+            breakpoints.push((path.clone(), cache_control.ttl()));
+        }
+        path.pop();
+    }
+}
+
+/// Set a breakpoint on the last cacheable block unless it already has one, returning its index.
+fn mark_last_cacheable(blocks: &mut [ContentBlock], ttl: Ttl) -> Option<usize> {
+    let (index, cache_control) =
+        blocks.iter_mut().enumerate().rev().find_map(|(index, block)| Some((index, block.cache_control_mut()?)))?;
+    if cache_control.is_some() {
+        return None;
+    }
+    *cache_control = Some(CacheControl::ephemeral(ttl));
+    Some(index)
+}
@@ -0,0 +1,88 @@
+//! Rendering of cited response text.
+
+use std::fmt::Write as _;
+
+use crate::types::{Citation, ContentBlock, MessagesResponse};
+
+/// Concatenate the text blocks of a response, marking cited spans with `[n]` and listing the
+/// cited passages as footnotes at the end.
+///
+/// Citations quoting the same passage of the same source share a footnote number.
+pub fn render_with_footnotes(content: &[ContentBlock]) -> String {
+    let mut text = String::new();
+    let mut footnotes: Vec<&Citation> = Vec::new();
+
+    for block in content {
+        let ContentBlock::Text { text: block_text, citations, .. } = block else {
+            continue;
+        };
+        text.push_str(block_text);
+        for citation in citations.iter().flatten() {
+            let number = match footnotes.iter().position(|known| same_passage(known, citation)) {
+                Some(position) => position + 1,
+                None => {
+                    footnotes.push(citation);
+                    footnotes.len()
+                }
+            };
+            let _ = write!(text, "[{number}]");
+        }
+    }
+
+    if !footnotes.is_empty() {
+        text.push_str("\n\n");
+        for (number, citation) in footnotes.iter().enumerate() {
+            let _ =
+                writeln!(text, "[{}] \"{}\" ({})", number + 1, citation.cited_text().trim(), source_label(citation));
+        }
+        text.pop();
+    }
+    text
+}
+
+fn same_passage(a: &Citation, b: &Citation) -> bool {
+    a.cited_text() == b.cited_text() && source_label(a) == source_label(b)
+}
+
+fn source_label(citation: &Citation) -> String {
+    let document = |index: &usize, title: &Option<String>| match title {
+        Some(title) => title.clone(),
+        None => format!("document {index}"),
+    };
+    match citation {
+        Citation::CharLocation { document_index, document_title, start_char_index, end_char_index, .. } => {
+            format!("{}, chars {start_char_index}-{end_char_index}", document(document_index, document_title))
+        }
+        Citation::PageLocation { document_index, document_title, start_page_number, end_page_number, .. } => {
+            let document = document(document_index, document_title);
+            if end_page_number.saturating_sub(*start_page_number) <= 1 {
+                format!("{document}, p. {start_page_number}")
+            } else {
+                format!("{document}, pp. {start_page_number}-{}", end_page_number - 1)
+            }
+        }
+        Citation::ContentBlockLocation {
+            document_index, document_title, start_block_index, end_block_index, ..
+        } => {
+            format!("{}, blocks {start_block_index}-{end_block_index}", document(document_index, document_title))
+        }
+        Citation::SearchResultLocation { source, title, .. } => match title {
+            Some(title) => format!("{title}, {source}"),
+            None => source.clone(),
+        },
+        Citation::WebSearchResultLocation { url, title, .. } => match title {
+            Some(title) => format!("{title}, {url}"),
+            None => url.clone(),
+        },
+        Citation::Unknown(citation) => {
+            citation.get("type").and_then(serde_json::Value::as_str).unwrap_or("unknown source").to_string()
+        }
+    }
+}
+
+impl MessagesResponse {
+    /// The response text with citations rendered as footnotes, see [`render_with_footnotes`].
+    pub fn text_with_footnotes(&self) -> String {
+        render_with_footnotes(&self.content)
+    }
+}
@@ -1,22 +1,33 @@
+use std::future::Future;
 use std::pin::Pin;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use backoff::ExponentialBackoff;
+use bytes::Bytes;
+use eventsource_stream::{EventStreamError, Eventsource};
 use futures_util::StreamExt;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, USER_AGENT};
-use reqwest_eventsource::{Event, EventSource, RequestBuilderExt};
+use reqwest::{Method, RequestBuilder};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use tokio_stream::Stream;
 
+use crate::beta::{Beta, BETA_HEADER};
 use crate::error::{AnthropicError, ErrorResponse};
-use crate::types::{MessagesRequest, MessagesResponse, MessagesStreamEvent};
+use crate::options::RequestOptions;
+use crate::rate_limit::RateLimitInfo;
+use crate::response::{request_id, Response};
+use crate::retry::{DefaultRetryPolicy, RetryPolicy};
+use crate::transport::{ReqwestTransport, Transport, TransportResponse};
+use crate::types::{CountTokensRequest, CountTokensResponse, MessagesRequest, MessagesResponse, MessagesStreamEvent};
 
 const DEFAULT_API_BASE: &str = "https://api.anthropic.com";
 const DEFAULT_API_VERSION: &str = "2023-06-01";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
 const API_KEY_HEADER: &str = "x-api-key";
 const VERSION_HEADER: &str = "anthropic-version";
-const BETA_HEADER: &str = "anthropic-beta";
 
 /// Configure and build an Anthropic API client.
 #[derive(Debug, Default)]
@@ -24,10 +35,14 @@ pub struct ClientBuilder {
     api_key: Option<String>,
     api_base: Option<String>,
     api_version: Option<String>,
-    beta: Option<String>,
+    betas: Vec<Beta>,
     timeout: Option<Duration>,
     backoff: Option<ExponentialBackoff>,
+    max_retry_time: Option<Duration>,
+    max_attempts: Option<u32>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
     http_client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
 }
 
 impl ClientBuilder {
@@ -50,11 +65,20 @@ impl ClientBuilder {
         self
     }
 
-    pub fn beta(mut self, beta: impl Into<String>) -> Self {
-        self.beta = Some(beta.into());
+    /// Enable a beta feature on every request; may be called repeatedly.
+    pub fn beta(mut self, beta: impl Into<Beta>) -> Self {
+        self.betas.push(beta.into());
         self
     }
 
+    pub fn betas(mut self, betas: impl IntoIterator<Item = impl Into<Beta>>) -> Self {
+        self.betas.extend(betas.into_iter().map(Into::into));
+        self
+    }
+
+    /// Timeout for each attempt, 60 seconds by default.
+    ///
+    /// It is set on every request, so custom transports read it from [`reqwest::Request::timeout`].
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
@@ -65,40 +89,86 @@ impl ClientBuilder {
         self
     }
 
+    /// Cap the total time spent retrying a request, including server-requested `retry-after` delays.
+    pub fn max_retry_time(mut self, max_retry_time: Duration) -> Self {
+        self.max_retry_time = Some(max_retry_time);
+        self
+    }
+
+    /// Limit the number of attempts per request, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Replace the [`DefaultRetryPolicy`] used to decide which failures are retried.
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
     pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
         self.http_client = Some(http_client);
         self
     }
 
+    /// Send requests through `transport` instead of a [`ReqwestTransport`].
+    ///
+    /// Requests are still built with reqwest types and carry the client or per-request timeout
+    /// in [`reqwest::Request::timeout`], which the transport is expected to honor.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
     pub fn build(self) -> Result<Client, AnthropicError> {
         let api_key = self.api_key.ok_or_else(|| AnthropicError::InvalidRequest("api_key is required".into()))?;
         let api_base = self.api_base.unwrap_or_else(|| DEFAULT_API_BASE.to_string());
         let api_version = self.api_version.unwrap_or_else(|| DEFAULT_API_VERSION.to_string());
-        let timeout = self.timeout.unwrap_or_else(|| Duration::from_secs(60));
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
         let http_client = match self.http_client {
             Some(client) => client,
-            None => reqwest::Client::builder().timeout(timeout).build()?,
+            None => reqwest::Client::builder().build()?,
         };
+        let transport = self.transport.unwrap_or_else(|| Arc::new(ReqwestTransport::new(http_client.clone())));
+
+        let mut backoff = self.backoff.unwrap_or_default();
+        if let Some(max_retry_time) = self.max_retry_time {
+            backoff.max_elapsed_time = Some(max_retry_time);
+        }
 
         Ok(Client {
             api_key,
             api_base,
             api_version,
-            beta: self.beta,
+            betas: self.betas,
             http_client,
-            backoff: self.backoff.unwrap_or_default(),
+            transport,
+            timeout,
+            backoff,
+            max_attempts: self.max_attempts,
+            retry_policy: self.retry_policy.unwrap_or_else(|| Arc::new(DefaultRetryPolicy)),
+            rate_limit: Arc::new(Mutex::new(None)),
+            options: RequestOptions::default(),
         })
     }
 }
 
 /// The client to interact with the Anthropic API.
+#[derive(Clone)]
 pub struct Client {
     api_key: String,
     api_base: String,
     api_version: String,
-    beta: Option<String>,
+    betas: Vec<Beta>,
     http_client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    timeout: Duration,
     backoff: ExponentialBackoff,
+    max_attempts: Option<u32>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    options: RequestOptions,
 }
 
 impl Client {
@@ -121,7 +191,7 @@ impl Client {
         }
 
         if let Ok(beta) = std::env::var("ANTHROPIC_BETA") {
-            builder = builder.beta(beta);
+            builder = builder.betas(Beta::parse_list(&beta));
         }
 
         if let Ok(timeout) = std::env::var("ANTHROPIC_TIMEOUT_SECS") {
@@ -145,16 +215,59 @@ impl Client {
         &self.api_version
     }
 
-    pub fn beta(&self) -> Option<&str> {
-        self.beta.as_deref()
+    pub fn betas(&self) -> &[Beta] {
+        &self.betas
     }
 
-    pub async fn messages(&self, mut request: MessagesRequest) -> Result<MessagesResponse, AnthropicError> {
+    /// A view of this client that applies `options` to every request it sends.
+    ///
+    /// The view shares its connection pool and rate limit state with this client. Options
+    /// accumulate when views are nested, with the innermost taking precedence.
+    pub fn with_options(&self, options: RequestOptions) -> Client {
+        let mut client = self.clone();
+        if let Some(max_attempts) = options.max_attempts {
+            client.max_attempts = Some(max_attempts);
+        }
+        if let Some(retry_policy) = &options.retry_policy {
+            client.retry_policy = Arc::clone(retry_policy);
+        }
+        client.options = client.options.merge(options);
+        client
+    }
+
+    /// Rate limit headers from the most recent response that carried any.
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.lock().ok().and_then(|rate_limit| rate_limit.clone())
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) -> Option<RateLimitInfo> {
+        let rate_limit = RateLimitInfo::from_headers(headers)?;
+        self.store_rate_limit(&rate_limit);
+        Some(rate_limit)
+    }
+
+    fn store_rate_limit(&self, rate_limit: &RateLimitInfo) {
+        if let Ok(mut latest) = self.rate_limit.lock() {
+            *latest = Some(rate_limit.clone());
+        }
+    }
+
+    pub async fn messages(&self, request: MessagesRequest) -> Result<MessagesResponse, AnthropicError> {
+        self.messages_with_response(request).await.map(Response::into_body)
+    }
+
+    /// Like [`Client::messages`], also returning the status, headers and request id.
+    pub async fn messages_with_response(
+        &self,
+        mut request: MessagesRequest,
+    ) -> Result<Response<MessagesResponse>, AnthropicError> {
         if matches!(request.stream, Some(true)) {
             return Err(AnthropicError::InvalidRequest("stream=true requests must use messages_stream".into()));
         }
         request.stream = None;
-        self.post("/v1/messages", &request).await
+        request.validate_cache_breakpoints()?;
+        let betas = request.required_betas();
+        self.post_with_betas("/v1/messages", &request, &betas).await
     }
 
     pub async fn messages_stream(
@@ -162,29 +275,100 @@ impl Client {
         mut request: MessagesRequest,
     ) -> Result<MessagesResponseStream, AnthropicError> {
         request.stream = Some(true);
-        self.post_stream("/v1/messages", &request).await
+        request.validate_cache_breakpoints()?;
+        let betas = request.required_betas();
+        self.post_stream("/v1/messages", &request, &betas).await
+    }
+
+    /// Count the input tokens a request would consume without creating a message.
+    ///
+    /// Accepts either a [`CountTokensRequest`] or a full [`MessagesRequest`], whose
+    /// generation-only fields are dropped.
+    pub async fn count_tokens(
+        &self,
+        request: impl Into<CountTokensRequest>,
+    ) -> Result<CountTokensResponse, AnthropicError> {
+        self.count_tokens_with_response(request).await.map(Response::into_body)
+    }
+
+    pub async fn count_tokens_with_response(
+        &self,
+        request: impl Into<CountTokensRequest>,
+    ) -> Result<Response<CountTokensResponse>, AnthropicError> {
+        let request = request.into();
+        request.validate_cache_breakpoints()?;
+        let betas = request.required_betas();
+        self.post_with_betas("/v1/messages/count_tokens", &request, &betas).await
     }
 
     fn headers(&self) -> Result<HeaderMap, AnthropicError> {
         let mut headers = HeaderMap::new();
         headers.insert(API_KEY_HEADER, HeaderValue::from_str(&self.api_key)?);
         headers.insert(VERSION_HEADER, HeaderValue::from_str(&self.api_version)?);
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
         headers.insert(USER_AGENT, HeaderValue::from_str(&format!("anthropic-rs/{}", env!("CARGO_PKG_VERSION")))?);
-        if let Some(beta) = &self.beta {
-            headers.insert(BETA_HEADER, HeaderValue::from_str(beta)?);
+        headers.extend(self.beta_headers(&[])?);
+        headers.extend(self.options.header_map()?);
+        Ok(headers)
+    }
+
+    /// Start a request against `path` with the default headers and request options applied.
+    pub(crate) fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, AnthropicError> {
+        let request = self.http_client.request(method, format!("{}{path}", self.api_base)).headers(self.headers()?);
+        Ok(request.timeout(self.options.timeout.unwrap_or(self.timeout)))
+    }
+
+    /// Attach `body` as JSON, including any extra body fields from the request options.
+    fn json<I>(&self, request: RequestBuilder, body: &I) -> Result<RequestBuilder, AnthropicError>
+    where
+        I: Serialize + ?Sized,
+    {
+        if self.options.extra_body.is_empty() {
+            return Ok(request.json(body));
+        }
+        Ok(request.json(&self.options.json_body(body)?))
+    }
+
+    /// The `anthropic-beta` header combining configured, per-request and `required` flags.
+    pub(crate) fn beta_headers(&self, required: &[Beta]) -> Result<HeaderMap, AnthropicError> {
+        let mut betas: Vec<&str> = Vec::new();
+        for beta in self.betas.iter().chain(&self.options.betas).chain(required) {
+            // `Custom` flags may themselves hold a comma-separated list.
+            for beta in beta.as_str().split(',').map(str::trim) {
+                if !beta.is_empty() && !betas.contains(&beta) {
+                    betas.push(beta);
+                }
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        if !betas.is_empty() {
+            headers.insert(BETA_HEADER, HeaderValue::from_str(&betas.join(","))?);
         }
         Ok(headers)
     }
 
-    async fn post<I, O>(&self, path: &str, request: &I) -> Result<O, AnthropicError>
+    pub(crate) async fn post<I, O>(&self, path: &str, request: &I) -> Result<Response<O>, AnthropicError>
     where
         I: Serialize + ?Sized,
         O: DeserializeOwned,
     {
-        let request =
-            self.http_client.post(format!("{}{path}", self.api_base)).headers(self.headers()?).json(request).build()?;
+        self.post_with_betas(path, request, &[]).await
+    }
+
+    /// Post `request` with the beta flags it depends on added to the configured ones.
+    pub(crate) async fn post_with_betas<I, O>(
+        &self,
+        path: &str,
+        request: &I,
+        betas: &[Beta],
+    ) -> Result<Response<O>, AnthropicError>
+    where
+        I: Serialize + ?Sized,
+        O: DeserializeOwned,
+    {
+        let builder = self.request(Method::POST, path)?.headers(self.beta_headers(betas)?);
+        let request = self.json(builder, request)?.build()?;
 
         self.execute(request).await
     }
@@ -193,143 +377,327 @@ impl Client {
         &self,
         path: &str,
         request: &I,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<MessagesStreamEvent, AnthropicError>> + Send>>, AnthropicError>
+        betas: &[Beta],
+    ) -> Result<MessagesResponseStream, AnthropicError>
     where
         I: Serialize + ?Sized,
     {
-        let event_source = self
-            .http_client
-            .post(format!("{}{path}", self.api_base))
-            .headers(self.headers()?)
-            .json(request)
-            .eventsource()
-            .map_err(|err| AnthropicError::EventSourceCannotClone(err.into()))?;
+        let builder = self.request(Method::POST, path)?.headers(self.beta_headers(betas)?);
+        let request = self.json(builder, request)?.build()?;
+
+        // Retries end once response headers arrive; after that a retry would replay the generation.
+        let Response { body, status, headers, request_id, .. } = self.execute_raw(request).await?;
+        let is_event_stream = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !is_event_stream {
+            let body = body.text().await?;
+            return Err(AnthropicError::UnexpectedResponse { status, body });
+        }
+
+        Ok(MessagesResponseStream { inner: stream(body, request_id.clone()), status, headers, request_id })
+    }
+
+    pub(crate) async fn get<O>(&self, path: &str) -> Result<Response<O>, AnthropicError>
+    where
+        O: DeserializeOwned,
+    {
+        let request = self.request(Method::GET, path)?.build()?;
 
-        Ok(stream(event_source).await)
+        self.execute(request).await
     }
 
-    async fn execute<O>(&self, request: reqwest::Request) -> Result<O, AnthropicError>
+    pub(crate) async fn get_with_query<Q, O>(&self, path: &str, query: &Q) -> Result<Response<O>, AnthropicError>
     where
+        Q: Serialize + ?Sized,
         O: DeserializeOwned,
     {
-        let client = self.http_client.clone();
+        let request = self.request(Method::GET, path)?.query(query).build()?;
+
+        self.execute(request).await
+    }
+
+    pub(crate) async fn get_raw(&self, path: &str) -> Result<Response<TransportResponse>, AnthropicError> {
+        let request = self.request(Method::GET, path)?.build()?;
+
+        self.execute_raw(request).await
+    }
+
+    pub(crate) async fn delete<O>(&self, path: &str) -> Result<Response<O>, AnthropicError>
+    where
+        O: DeserializeOwned,
+    {
+        let request = self.request(Method::DELETE, path)?.build()?;
+
+        self.execute(request).await
+    }
+
+    /// Send a request with retries and deserialize the JSON body of the successful response.
+    ///
+    /// The body is read inside the retry loop, so a connection dropped while reading it is retried.
+    pub(crate) async fn execute<O>(&self, request: reqwest::Request) -> Result<Response<O>, AnthropicError>
+    where
+        O: DeserializeOwned,
+    {
+        self.send(request, |response| async move {
+            let bytes = response.bytes().await?;
+            Ok(serde_json::from_slice::<O>(bytes.as_ref())?)
+        })
+        .await
+    }
+
+    /// Send a request with retries, returning the successful response with its body unread.
+    pub(crate) async fn execute_raw(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<Response<TransportResponse>, AnthropicError> {
+        self.send(request, |response| async move { Ok(response) }).await
+    }
+
+    /// Send a request with retries until a successful response is handed to `read` without error.
+    async fn send<T, F, Fut>(&self, request: reqwest::Request, read: F) -> Result<Response<T>, AnthropicError>
+    where
+        F: Fn(TransportResponse) -> Fut,
+        Fut: Future<Output = Result<T, AnthropicError>>,
+    {
+        let started = Instant::now();
 
         match request.try_clone() {
             Some(request) => {
-                backoff::future::retry(self.backoff.clone(), || {
+                let mut attempts = 0;
+
+                let (body, status, headers) = backoff::future::retry(self.backoff.clone(), || {
+                    attempts += 1;
+                    let attempt = attempts;
                     let request = request.try_clone().ok_or_else(|| {
                         backoff::Error::Permanent(AnthropicError::InvalidRequest("request could not be cloned".into()))
                     });
-                    let client = client.clone();
+                    let read = &read;
                     async move {
                         let request = request?;
-                        let response = client
-                            .execute(request)
-                            .await
-                            .map_err(AnthropicError::Http)
-                            .map_err(backoff::Error::Permanent)?;
-
-                        let status = response.status();
-                        let bytes =
-                            response.bytes().await.map_err(AnthropicError::Http).map_err(backoff::Error::Permanent)?;
-
-                        if !status.is_success() {
-                            let error = parse_error(status.as_u16(), bytes.as_ref());
-                            if status.as_u16() == 429 || status.as_u16() == 529 {
-                                return Err(backoff::Error::Transient { err: error, retry_after: None });
+                        let (status, rate_limit, error) = match self.transport.send(request).await {
+                            Ok(response) => {
+                                let status = response.status;
+                                let rate_limit = self.record_rate_limit(&response.headers);
+                                let headers = response.headers.clone();
+                                let error = if response.is_success() {
+                                    match read(response).await {
+                                        Ok(body) => return Ok((body, status, headers)),
+                                        Err(error) => error,
+                                    }
+                                } else {
+                                    match response.bytes().await {
+                                        Ok(bytes) => parse_error(status, &headers, bytes.as_ref()),
+                                        Err(error) => error,
+                                    }
+                                };
+                                (Some(status), rate_limit, error)
                             }
-                            return Err(backoff::Error::Permanent(error));
-                        }
+                            Err(error) => (None, None, error),
+                        };
 
-                        let response = serde_json::from_slice::<O>(bytes.as_ref())
-                            .map_err(AnthropicError::Deserialize)
-                            .map_err(backoff::Error::Permanent)?;
-                        Ok(response)
+                        Err(self.retry_error(error, status, rate_limit.as_ref(), attempt, started))
                     }
                 })
-                .await
+                .await?;
+                let request_id = request_id(&headers);
+                Ok(Response { body, status, headers, request_id, retries: attempts - 1, elapsed: started.elapsed() })
             }
             None => {
-                let response = client.execute(request).await?;
-                process_response(response).await
+                let response = self.transport.send(request).await?;
+                self.record_rate_limit(&response.headers);
+                let response = check_response(response).await?;
+                let (status, headers) = (response.status, response.headers.clone());
+                let body = read(response).await?;
+                let request_id = request_id(&headers);
+                Ok(Response { body, status, headers, request_id, retries: 0, elapsed: started.elapsed() })
+            }
+        }
+    }
+
+    /// Classify a failed attempt as transient or permanent according to the retry settings.
+    fn retry_error(
+        &self,
+        error: AnthropicError,
+        status: Option<u16>,
+        rate_limit: Option<&RateLimitInfo>,
+        attempt: u32,
+        started: Instant,
+    ) -> backoff::Error<AnthropicError> {
+        if !self.retry_policy.should_retry(status, &error) {
+            return backoff::Error::Permanent(error);
+        }
+        if self.max_attempts.is_some_and(|max_attempts| attempt >= max_attempts) {
+            return backoff::Error::Permanent(error);
+        }
+
+        let retry_after = rate_limit.and_then(RateLimitInfo::retry_delay);
+        // backoff keeps honoring explicit delays past its own deadline, so enforce it here.
+        if let (Some(delay), Some(max_retry_time)) = (retry_after, self.backoff.max_elapsed_time) {
+            if started.elapsed() + delay > max_retry_time {
+                return backoff::Error::Permanent(error);
             }
         }
+
+        backoff::Error::Transient { err: error, retry_after }
     }
 }
 
-pub type MessagesResponseStream = Pin<Box<dyn Stream<Item = Result<MessagesStreamEvent, AnthropicError>> + Send>>;
+/// Events of a streamed message, along with the headers of the response carrying them.
+pub struct MessagesResponseStream {
+    inner: Pin<Box<dyn Stream<Item = Result<MessagesStreamEvent, AnthropicError>> + Send>>,
+    status: u16,
+    headers: HeaderMap,
+    request_id: Option<String>,
+}
 
-async fn process_response<O>(response: reqwest::Response) -> Result<O, AnthropicError>
-where
-    O: DeserializeOwned,
-{
-    let status = response.status();
-    let bytes = response.bytes().await?;
+impl MessagesResponseStream {
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The `request-id` header, to quote when contacting support.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl Stream for MessagesResponseStream {
+    type Item = Result<MessagesStreamEvent, AnthropicError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
 
-    if !status.is_success() {
-        return Err(parse_error(status.as_u16(), bytes.as_ref()));
+async fn check_response(response: TransportResponse) -> Result<TransportResponse, AnthropicError> {
+    if response.is_success() {
+        return Ok(response);
     }
 
-    let response = serde_json::from_slice::<O>(bytes.as_ref())?;
-    Ok(response)
+    let status = response.status;
+    let headers = response.headers.clone();
+    let bytes = response.bytes().await?;
+    Err(parse_error(status, &headers, bytes.as_ref()))
 }
 
-fn parse_error(status: u16, bytes: &[u8]) -> AnthropicError {
-    if let Ok(error) = serde_json::from_slice::<ErrorResponse>(bytes) {
-        return AnthropicError::Api(error.error);
+fn parse_error(status: u16, headers: &HeaderMap, bytes: &[u8]) -> AnthropicError {
+    if let Ok(mut error) = serde_json::from_slice::<ErrorResponse>(bytes) {
+        error.error.status = Some(status);
+        error.error.request_id = request_id(headers);
+        error.error.rate_limit = RateLimitInfo::from_headers(headers);
+        return AnthropicError::Api(Box::new(error.error));
     }
 
     let body = String::from_utf8_lossy(bytes).to_string();
     AnthropicError::UnexpectedResponse { status, body }
 }
 
-async fn stream(
-    mut event_source: EventSource,
+/// Parse the server-sent events of `response`; `request_id` is attached to `error` events.
+fn stream(
+    response: TransportResponse,
+    request_id: Option<String>,
 ) -> Pin<Box<dyn Stream<Item = Result<MessagesStreamEvent, AnthropicError>> + Send>> {
+    let mut events = response.body.eventsource();
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
     tokio::spawn(async move {
-        while let Some(ev) = event_source.next().await {
+        while let Some(ev) = events.next().await {
             match ev {
-                Ok(Event::Open) => continue,
-                Ok(Event::Message(message)) => {
+                Ok(message) => {
                     let event = message.event.as_str();
                     if event == "ping" {
                         continue;
                     }
 
-                    let response = if event == "error" {
+                    let (response, cancel) = if event == "error" {
                         match serde_json::from_str::<ErrorResponse>(&message.data) {
-                            Ok(error) => Err(AnthropicError::Api(error.error)),
-                            Err(err) => Err(AnthropicError::Deserialize(err)),
+                            Ok(ErrorResponse { mut error }) => {
+                                error.request_id.clone_from(&request_id);
+                                (Err(AnthropicError::Api(Box::new(error))), true)
+                            }
+                            Err(err) => (Err(AnthropicError::Deserialize(err)), true),
                         }
                     } else {
-                        match serde_json::from_str::<MessagesStreamEvent>(&message.data) {
-                            Ok(output) => Ok(output),
-                            Err(err) => Err(AnthropicError::Deserialize(err)),
-                        }
+                        // Unknown event types parse as `MessagesStreamEvent::Unknown`; an event that is not
+                        // even JSON is reported but does not end the stream.
+                        (
+                            serde_json::from_str::<MessagesStreamEvent>(&message.data).map_err(AnthropicError::from),
+                            false,
+                        )
                     };
 
-                    let cancel = response.is_err();
                     if tx.send(response).is_err() || cancel {
                         break;
                     }
                 }
                 Err(e) => {
-                    if let reqwest_eventsource::Error::StreamEnded = e {
-                        break;
-                    }
-
-                    let error = AnthropicError::InvalidRequest(format!("stream error: {e}"));
+                    let error = match e {
+                        EventStreamError::Transport(err) => err,
+                        EventStreamError::Utf8(err) => {
+                            AnthropicError::EventSource(Box::new(EventStreamError::Utf8(err)))
+                        }
+                        EventStreamError::Parser(err) => {
+                            AnthropicError::EventSource(Box::new(EventStreamError::Parser(err)))
+                        }
+                    };
                     if tx.send(Err(error)).is_err() {
                         break;
                     }
                 }
             }
         }
-
-        event_source.close();
     });
 
     Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx))
 }
+
+/// Decode a newline-delimited JSON response body one line at a time as chunks arrive.
+pub(crate) fn jsonl_stream<T>(
+    response: TransportResponse,
+) -> Pin<Box<dyn Stream<Item = Result<T, AnthropicError>> + Send>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let state = (response.body, Vec::<u8>::new(), false);
+
+    let stream = futures_util::stream::unfold(state, |(mut bytes, mut buffer, mut done)| async move {
+        loop {
+            if let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let item = serde_json::from_slice::<T>(&line).map_err(AnthropicError::Deserialize);
+                return Some((item, (bytes, buffer, done)));
+            }
+
+            if done {
+                if buffer.iter().all(u8::is_ascii_whitespace) {
+                    return None;
+                }
+                let line = std::mem::take(&mut buffer);
+                let item = serde_json::from_slice::<T>(&line).map_err(AnthropicError::Deserialize);
+                return Some((item, (bytes, buffer, done)));
+            }
+
+            match bytes.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    buffer.clear();
+                    return Some((Err(err), (bytes, buffer, true)));
+                }
+                None => done = true,
+            }
+        }
+    });
+
+    Box::pin(stream)
+}
+
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, AnthropicError>> + Send>>;
@@ -1,9 +1,13 @@
+use std::convert::Infallible;
 use std::fmt;
 
+use eventsource_stream::EventStreamError;
 use reqwest::header::InvalidHeaderValue;
-use reqwest_eventsource::{CannotCloneRequestError, Error as EventSourceError};
 use serde::{Deserialize, Serialize};
 
+use crate::rate_limit::RateLimitInfo;
+use crate::retry::{DefaultRetryPolicy, RetryPolicy};
+
 /// Errors returned by the Anthropic SDK.
 #[derive(Debug, thiserror::Error)]
 pub enum AnthropicError {
@@ -12,7 +16,7 @@ pub enum AnthropicError {
     Http(#[from] reqwest::Error),
     /// Anthropic API returned an error payload.
     #[error("api error: {0}")]
-    Api(ApiError),
+    Api(Box<ApiError>),
     /// Error when a response cannot be deserialized into a Rust type.
     #[error("failed to deserialize api response: {0}")]
     Deserialize(#[from] serde_json::Error),
@@ -25,25 +29,152 @@ pub enum AnthropicError {
     /// Invalid header value provided for request headers.
     #[error("invalid header value: {0}")]
     InvalidHeaderValue(#[from] InvalidHeaderValue),
-    /// Eventsource setup failure.
+    /// Failure reported by a custom [`Transport`](crate::transport::Transport).
+    #[error("transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    /// The response body is not a valid server-sent event stream.
     #[error("eventsource error: {0}")]
-    EventSource(#[from] Box<EventSourceError>),
-    /// Eventsource request could not be cloned.
-    #[error("eventsource request could not be cloned: {0}")]
-    EventSourceCannotClone(#[from] Box<CannotCloneRequestError>),
+    EventSource(#[from] Box<EventStreamError<Infallible>>),
+    /// The event stream ended before a complete message was received.
+    #[error("stream ended before a complete message was received")]
+    IncompleteStream,
+    /// Structured output could not be deserialized into the requested type.
+    #[error("structured output does not match the expected type: {source}")]
+    StructuredOutput {
+        /// The text returned by the model.
+        raw: String,
+        #[source]
+        source: serde_json::Error,
+    },
     /// Unexpected response payload.
     #[error("unexpected response (status {status}): {body}")]
     UnexpectedResponse { status: u16, body: String },
 }
 
+impl AnthropicError {
+    /// Rate limit information attached to an API error response.
+    pub fn rate_limit(&self) -> Option<&RateLimitInfo> {
+        match self {
+            AnthropicError::Api(error) => error.rate_limit.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// HTTP status code of the failed response, if one was received.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            AnthropicError::Api(error) => error.status,
+            AnthropicError::UnexpectedResponse { status, .. } => Some(*status),
+            AnthropicError::Http(error) => error.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Classified API error type, for errors returned by the API.
+    pub fn kind(&self) -> Option<&ApiErrorKind> {
+        match self {
+            AnthropicError::Api(error) => Some(&error.error_type),
+            _ => None,
+        }
+    }
+
+    /// The `request-id` of the failed response, useful when contacting support.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            AnthropicError::Api(error) => error.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(429) || matches!(self.kind(), Some(ApiErrorKind::RateLimit))
+    }
+
+    /// Whether the [`DefaultRetryPolicy`] would retry this error.
+    pub fn is_retryable(&self) -> bool {
+        DefaultRetryPolicy.should_retry(self.status(), self)
+    }
+}
+
+/// The `type` of an API error payload.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ApiErrorKind {
+    InvalidRequest,
+    Authentication,
+    Permission,
+    NotFound,
+    RequestTooLarge,
+    RateLimit,
+    Api,
+    Overloaded,
+    Unknown(String),
+}
+
+impl ApiErrorKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ApiErrorKind::InvalidRequest => "invalid_request_error",
+            ApiErrorKind::Authentication => "authentication_error",
+            ApiErrorKind::Permission => "permission_error",
+            ApiErrorKind::NotFound => "not_found_error",
+            ApiErrorKind::RequestTooLarge => "request_too_large",
+            ApiErrorKind::RateLimit => "rate_limit_error",
+            ApiErrorKind::Api => "api_error",
+            ApiErrorKind::Overloaded => "overloaded_error",
+            ApiErrorKind::Unknown(kind) => kind,
+        }
+    }
+}
+
+impl From<String> for ApiErrorKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "invalid_request_error" => ApiErrorKind::InvalidRequest,
+            "authentication_error" => ApiErrorKind::Authentication,
+            "permission_error" => ApiErrorKind::Permission,
+            "not_found_error" => ApiErrorKind::NotFound,
+            "request_too_large" => ApiErrorKind::RequestTooLarge,
+            "rate_limit_error" => ApiErrorKind::RateLimit,
+            "api_error" => ApiErrorKind::Api,
+            "overloaded_error" => ApiErrorKind::Overloaded,
+            _ => ApiErrorKind::Unknown(kind),
+        }
+    }
+}
+
+impl From<ApiErrorKind> for String {
+    fn from(kind: ApiErrorKind) -> Self {
+        match kind {
+            ApiErrorKind::Unknown(kind) => kind,
+            kind => kind.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Anthropic API error payload.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiError {
     pub message: String,
     #[serde(rename = "type")]
-    pub error_type: String,
+    pub error_type: ApiErrorKind,
     pub param: Option<serde_json::Value>,
     pub code: Option<serde_json::Value>,
+    /// HTTP status of the response, absent for errors sent as stream events.
+    #[serde(skip)]
+    pub status: Option<u16>,
+    /// Value of the `request-id` response header.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+    /// Rate limit headers sent with the error response, if any.
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimitInfo>,
 }
 
 impl fmt::Display for ApiError {
@@ -52,7 +183,8 @@ impl fmt::Display for ApiError {
     }
 }
 
+/// Envelope wrapping an [`ApiError`] in error responses and batch results.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct ErrorResponse {
+pub struct ErrorResponse {
     pub error: ApiError,
 }
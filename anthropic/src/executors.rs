@@ -0,0 +1,359 @@
+//! Reference executors for the Anthropic-defined bash and text editor tools.
+//!
+//! Both operate inside a root directory. The text editor refuses paths that resolve outside
+//! of it; the bash executor only uses it as the working directory and is not a sandbox.
+
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::tool_runner::ToolRunner;
+
+const DEFAULT_BASH_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 32 * 1024;
+/// Dangling symlinks followed while resolving a path, matching the Linux `ELOOP` limit.
+const MAX_SYMLINKS: usize = 40;
+
+#[derive(Deserialize)]
+struct BashInput {
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    restart: bool,
+}
+
+/// Runs `bash` tool commands with `bash -c`, one process per command.
+#[derive(Clone, Debug)]
+pub struct BashExecutor {
+    root: PathBuf,
+    timeout: Duration,
+    max_output_bytes: usize,
+}
+
+impl BashExecutor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), timeout: DEFAULT_BASH_TIMEOUT, max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Truncate combined stdout and stderr to this many bytes.
+    pub fn max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Execute a `bash` tool call, returning its output or an error message for the model.
+    pub async fn execute(&self, input: serde_json::Value) -> Result<String, String> {
+        let input: BashInput = serde_json::from_value(input).map_err(|err| format!("invalid input: {err}"))?;
+        if input.restart {
+            return Ok("tool has been restarted.".into());
+        }
+        let command = input.command.ok_or("missing `command`")?;
+
+        let output = tokio::process::Command::new("bash")
+            .arg("-c")
+            .arg(&command)
+            .current_dir(&self.root)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(self.timeout, output)
+            .await
+            .map_err(|_| format!("command timed out after {}s", self.timeout.as_secs()))?
+            .map_err(|err| format!("failed to run command: {err}"))?;
+
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.is_empty() {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&stderr);
+        }
+        truncate(&mut text, self.max_output_bytes);
+
+        if output.status.success() {
+            Ok(text)
+        } else {
+            let status = output.status.code().map_or_else(|| "killed by signal".into(), |code| code.to_string());
+            Err(format!("{text}\nexit status: {status}"))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum TextEditorCommand {
+    View {
+        path: String,
+        #[serde(default)]
+        view_range: Option<(i64, i64)>,
+    },
+    Create {
+        path: String,
+        file_text: String,
+    },
+    StrReplace {
+        path: String,
+        old_str: String,
+        #[serde(default)]
+        new_str: String,
+    },
+    Insert {
+        path: String,
+        insert_line: usize,
+        // `text_editor_20250124` sends the inserted text as `new_str`.
+        #[serde(alias = "new_str")]
+        insert_text: String,
+    },
+    UndoEdit {},
+}
+
+/// Executes `text_editor` tool calls against files below a root directory.
+#[derive(Clone, Debug)]
+pub struct TextEditorExecutor {
+    root: PathBuf,
+    name: String,
+    max_characters: Option<usize>,
+}
+
+impl TextEditorExecutor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), name: "str_replace_based_edit_tool".into(), max_characters: None }
+    }
+
+    /// Tool name to register, `str_replace_editor` for `text_editor_20250124`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Truncate `view` output to this many characters.
+    pub fn max_characters(mut self, max_characters: usize) -> Self {
+        self.max_characters = Some(max_characters);
+        self
+    }
+
+    /// Execute a text editor tool call, returning its output or an error message for the model.
+    pub async fn execute(&self, input: serde_json::Value) -> Result<String, String> {
+        let command: TextEditorCommand =
+            serde_json::from_value(input).map_err(|err| format!("invalid input: {err}"))?;
+
+        match command {
+            TextEditorCommand::View { path, view_range } => self.view(&path, view_range).await,
+            TextEditorCommand::Create { path, file_text } => {
+                let resolved = self.resolve(&path)?;
+                if let Some(parent) = resolved.parent() {
+                    tokio::fs::create_dir_all(parent).await.map_err(|err| format!("cannot create {path}: {err}"))?;
+                }
+                tokio::fs::write(&resolved, file_text).await.map_err(|err| format!("cannot write {path}: {err}"))?;
+                Ok(format!("File created successfully at: {path}"))
+            }
+            TextEditorCommand::StrReplace { path, old_str, new_str } => {
+                let resolved = self.resolve(&path)?;
+                let content = read_file(&resolved, &path).await?;
+                let matches: Vec<usize> = content.match_indices(&old_str).map(|(offset, _)| offset).collect();
+                match matches.as_slice() {
+                    [] => Err(format!("No replacement was performed, old_str did not appear verbatim in {path}.")),
+                    [_] => {
+                        let content = content.replacen(&old_str, &new_str, 1);
+                        tokio::fs::write(&resolved, content)
+                            .await
+                            .map_err(|err| format!("cannot write {path}: {err}"))?;
+                        Ok(format!("The file {path} has been edited."))
+                    }
+                    offsets => {
+                        let lines: Vec<String> = offsets
+                            .iter()
+                            .map(|offset| (content[..*offset].matches('\n').count() + 1).to_string())
+                            .collect();
+                        Err(format!(
+                            "No replacement was performed. Multiple occurrences of old_str in lines {}. \
+                             Please ensure it is unique.",
+                            lines.join(", ")
+                        ))
+                    }
+                }
+            }
+            TextEditorCommand::Insert { path, insert_line, insert_text } => {
+                let resolved = self.resolve(&path)?;
+                let content = read_file(&resolved, &path).await?;
+                let mut lines: Vec<&str> = content.lines().collect();
+                if insert_line > lines.len() {
+                    return Err(format!(
+                        "Invalid `insert_line` {insert_line}, it should be within [0, {}].",
+                        lines.len()
+                    ));
+                }
+                lines.splice(insert_line..insert_line, insert_text.lines());
+                let mut content = lines.join("\n");
+                content.push('\n');
+                tokio::fs::write(&resolved, content).await.map_err(|err| format!("cannot write {path}: {err}"))?;
+                Ok(format!("The file {path} has been edited."))
+            }
+            TextEditorCommand::UndoEdit {} => Err("undo_edit is not supported.".into()),
+        }
+    }
+
+    async fn view(&self, path: &str, view_range: Option<(i64, i64)>) -> Result<String, String> {
+        let resolved = self.resolve(path)?;
+        let metadata = tokio::fs::metadata(&resolved).await.map_err(|err| format!("cannot access {path}: {err}"))?;
+
+        let mut output = if metadata.is_dir() {
+            if view_range.is_some() {
+                return Err("`view_range` is not allowed when `path` points to a directory.".into());
+            }
+            let mut entries = Vec::new();
+            list_dir(&resolved, &resolved, 2, &mut entries).map_err(|err| format!("cannot list {path}: {err}"))?;
+            entries.sort();
+            format!("Files and directories up to 2 levels deep in {path}:\n{}", entries.join("\n"))
+        } else {
+            let content = read_file(&resolved, path).await?;
+            let lines: Vec<&str> = content.lines().collect();
+            let (start, end) = match view_range {
+                None => (1, lines.len()),
+                Some((start, end)) => {
+                    let end = if end == -1 { lines.len() as i64 } else { end };
+                    if start < 1 || start > end || end > lines.len() as i64 {
+                        return Err(format!(
+                            "Invalid `view_range` [{start}, {end}] for a file with {} lines.",
+                            lines.len()
+                        ));
+                    }
+                    (start as usize, end as usize)
+                }
+            };
+            let mut numbered = String::new();
+            for (number, line) in lines.iter().enumerate().take(end).skip(start - 1) {
+                let _ = writeln!(numbered, "{:>6}\t{line}", number + 1);
+            }
+            numbered
+        };
+
+        if let Some(max_characters) = self.max_characters {
+            if let Some((offset, _)) = output.char_indices().nth(max_characters) {
+                output.truncate(offset);
+                output.push_str("\n<output truncated>");
+            }
+        }
+        Ok(output)
+    }
+
+    /// Resolve `path` below the root, rejecting anything that would escape it.
+    ///
+    /// Relative paths are taken relative to the root; absolute paths must lie within it.
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let root = self.root.canonicalize().map_err(|err| format!("invalid root directory: {err}"))?;
+        let requested = Path::new(path);
+        let relative = if requested.is_absolute() {
+            requested
+                .strip_prefix(&root)
+                .or_else(|_| requested.strip_prefix(&self.root))
+                .map_err(|_| format!("path {path} is outside of the allowed directory"))?
+        } else {
+            requested
+        };
+
+        let mut resolved = root.clone();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => resolved.push(part),
+                Component::CurDir => (),
+                Component::ParentDir if resolved != root => {
+                    resolved.pop();
+                }
+                _ => return Err(format!("path {path} is outside of the allowed directory")),
+            }
+        }
+
+        // Follow symlinks on the existing part of the path so links cannot point outside the root.
+        // A dangling link counts as existing: writing through it creates its target, so that
+        // target is checked instead.
+        let mut existing = resolved.clone();
+        let mut links = 0;
+        loop {
+            match existing.symlink_metadata() {
+                Ok(metadata) if metadata.file_type().is_symlink() && !existing.exists() => {
+                    links += 1;
+                    if links > MAX_SYMLINKS {
+                        return Err(format!("cannot access {path}: too many levels of symbolic links"));
+                    }
+                    let target = std::fs::read_link(&existing).map_err(|err| format!("cannot access {path}: {err}"))?;
+                    existing = existing.parent().unwrap_or(&root).join(target);
+                }
+                Ok(_) => break,
+                Err(_) => existing = existing.parent().unwrap_or(&root).to_path_buf(),
+            }
+        }
+        let canonical = existing.canonicalize().map_err(|err| format!("cannot access {path}: {err}"))?;
+        if !canonical.starts_with(&root) {
+            return Err(format!("path {path} is outside of the allowed directory"));
+        }
+
+        Ok(resolved)
+    }
+}
+
+async fn read_file(resolved: &Path, path: &str) -> Result<String, String> {
+    tokio::fs::read_to_string(resolved).await.map_err(|err| format!("cannot read {path}: {err}"))
+}
+
+fn list_dir(root: &Path, dir: &Path, depth: usize, entries: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let display = path.strip_prefix(root).unwrap_or(&path).display().to_string();
+        if entry.file_type()?.is_dir() {
+            entries.push(format!("{display}/"));
+            if depth > 1 {
+                list_dir(root, &path, depth - 1, entries)?;
+            }
+        } else {
+            entries.push(display);
+        }
+    }
+    Ok(())
+}
+
+fn truncate(text: &mut String, max_bytes: usize) {
+    if text.len() <= max_bytes {
+        return;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str("\n<output truncated>");
+}
+
+impl ToolRunner {
+    /// Handle `bash` tool calls with `executor`.
+    pub fn bash(self, executor: BashExecutor) -> Self {
+        let executor = Arc::new(executor);
+        self.tool("bash", move |input| {
+            let executor = Arc::clone(&executor);
+            async move { executor.execute(input).await }
+        })
+    }
+
+    /// Handle text editor tool calls with `executor`, registered under its configured name.
+    pub fn text_editor(self, executor: TextEditorExecutor) -> Self {
+        let name = executor.name.clone();
+        let executor = Arc::new(executor);
+        self.tool(name, move |input| {
+            let executor = Arc::clone(&executor);
+            async move { executor.execute(input).await }
+        })
+    }
+}
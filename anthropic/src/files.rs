@@ -0,0 +1,212 @@
+//! Types and client methods for the Files API.
+
+use std::path::Path;
+
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Method, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+use crate::beta::Beta;
+use crate::client::{ByteStream, Client};
+use crate::error::AnthropicError;
+use crate::response::Response;
+use crate::types::{ListParams, Page};
+
+/// Metadata of an uploaded file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// RFC 3339 timestamp of the upload.
+    pub created_at: String,
+    #[serde(default)]
+    pub downloadable: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeletedFile {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub deleted_type: String,
+}
+
+/// File contents to upload, built from bytes, a path or an async reader.
+///
+/// Uploads from bytes are retried like any other request. Streamed uploads, from a path or a
+/// reader, cannot be replayed and are sent exactly once.
+#[derive(Debug)]
+pub struct FileUpload {
+    filename: String,
+    mime_type: String,
+    body: UploadBody,
+}
+
+#[derive(Debug)]
+enum UploadBody {
+    Bytes(Vec<u8>),
+    Stream { body: Body, length: Option<u64> },
+}
+
+impl FileUpload {
+    pub fn from_bytes(filename: impl Into<String>, mime_type: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        Self { filename: filename.into(), mime_type: mime_type.into(), body: UploadBody::Bytes(bytes.into()) }
+    }
+
+    /// Stream the file at `path`, guessing the MIME type from its extension.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, AnthropicError> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|err| AnthropicError::InvalidRequest(format!("cannot open {}: {err}", path.display())))?;
+        let length = file
+            .metadata()
+            .await
+            .map_err(|err| AnthropicError::InvalidRequest(format!("cannot read {}: {err}", path.display())))?
+            .len();
+        let filename = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+
+        let body = Body::wrap_stream(ReaderStream::new(file));
+        let body = UploadBody::Stream { body, length: Some(length) };
+        Ok(Self { filename, mime_type: mime_type_for(path).to_string(), body })
+    }
+
+    pub fn from_reader<R>(filename: impl Into<String>, mime_type: impl Into<String>, reader: R) -> Self
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        let body = UploadBody::Stream { body: Body::wrap_stream(ReaderStream::new(reader)), length: None };
+        Self { filename: filename.into(), mime_type: mime_type.into(), body }
+    }
+
+    /// Attach the upload as a `multipart/form-data` body with a single `file` field.
+    fn attach(self, request: RequestBuilder) -> Result<RequestBuilder, AnthropicError> {
+        match self.body {
+            // reqwest always streams multipart forms, which makes the request impossible to
+            // clone for a retry, so in-memory uploads are encoded here instead.
+            UploadBody::Bytes(bytes) => {
+                if self.mime_type.parse::<HeaderValue>().is_err() {
+                    return Err(AnthropicError::InvalidRequest(format!("invalid MIME type {:?}", self.mime_type)));
+                }
+                let boundary = Form::new().boundary().to_string();
+                let filename = self.filename.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A");
+                let mut body = format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+                     Content-Type: {}\r\n\r\n",
+                    self.mime_type
+                )
+                .into_bytes();
+                body.extend_from_slice(&bytes);
+                body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+                Ok(request.header(CONTENT_TYPE, format!("multipart/form-data; boundary={boundary}")).body(body))
+            }
+            UploadBody::Stream { body, length } => {
+                let part = match length {
+                    Some(length) => Part::stream_with_length(body, length),
+                    None => Part::stream(body),
+                };
+                let part = part.file_name(self.filename).mime_str(&self.mime_type)?;
+                Ok(request.multipart(Form::new().part("file", part)))
+            }
+        }
+    }
+}
+
+pub(crate) fn mime_type_for(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("txt") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+impl Client {
+    pub async fn upload_file(&self, upload: FileUpload) -> Result<FileMetadata, AnthropicError> {
+        self.upload_file_with_response(upload).await.map(Response::into_body)
+    }
+
+    pub async fn upload_file_with_response(
+        &self,
+        upload: FileUpload,
+    ) -> Result<Response<FileMetadata>, AnthropicError> {
+        let request = self.request(Method::POST, "/v1/files")?.headers(self.beta_headers(&[Beta::FilesApi])?);
+        let request = upload.attach(request)?.build()?;
+
+        self.execute(request).await
+    }
+
+    pub async fn list_files(&self, params: &ListParams) -> Result<Page<FileMetadata>, AnthropicError> {
+        self.list_files_with_response(params).await.map(Response::into_body)
+    }
+
+    pub async fn list_files_with_response(
+        &self,
+        params: &ListParams,
+    ) -> Result<Response<Page<FileMetadata>>, AnthropicError> {
+        let request = self
+            .request(Method::GET, "/v1/files")?
+            .headers(self.beta_headers(&[Beta::FilesApi])?)
+            .query(params)
+            .build()?;
+
+        self.execute(request).await
+    }
+
+    pub async fn get_file_metadata(&self, file_id: &str) -> Result<FileMetadata, AnthropicError> {
+        self.get_file_metadata_with_response(file_id).await.map(Response::into_body)
+    }
+
+    pub async fn get_file_metadata_with_response(
+        &self,
+        file_id: &str,
+    ) -> Result<Response<FileMetadata>, AnthropicError> {
+        let request = self
+            .request(Method::GET, &format!("/v1/files/{file_id}"))?
+            .headers(self.beta_headers(&[Beta::FilesApi])?)
+            .build()?;
+
+        self.execute(request).await
+    }
+
+    /// Download the contents of a file as a stream of chunks.
+    pub async fn download_file(&self, file_id: &str) -> Result<ByteStream, AnthropicError> {
+        self.download_file_with_response(file_id).await.map(Response::into_body)
+    }
+
+    /// Like [`Client::download_file`], with the response headers; `elapsed` stops at the headers.
+    pub async fn download_file_with_response(&self, file_id: &str) -> Result<Response<ByteStream>, AnthropicError> {
+        let request = self
+            .request(Method::GET, &format!("/v1/files/{file_id}/content"))?
+            .headers(self.beta_headers(&[Beta::FilesApi])?)
+            .build()?;
+
+        Ok(self.execute_raw(request).await?.map(|response| response.body))
+    }
+
+    pub async fn delete_file(&self, file_id: &str) -> Result<DeletedFile, AnthropicError> {
+        self.delete_file_with_response(file_id).await.map(Response::into_body)
+    }
+
+    pub async fn delete_file_with_response(&self, file_id: &str) -> Result<Response<DeletedFile>, AnthropicError> {
+        let request = self
+            .request(Method::DELETE, &format!("/v1/files/{file_id}"))?
+            .headers(self.beta_headers(&[Beta::FilesApi])?)
+            .build()?;
+
+        self.execute(request).await
+    }
+}
@@ -25,9 +25,30 @@
 //! }
 //! ```
 
+pub mod batches;
+pub mod beta;
+pub mod cache;
+pub mod citations;
 pub mod client;
 pub mod error;
+#[cfg(feature = "executors")]
+pub mod executors;
+pub mod files;
+pub mod models;
+pub mod options;
+pub mod partial_json;
+pub mod rate_limit;
+pub mod response;
+pub mod retry;
+pub mod streaming;
+pub mod structured;
+pub mod tool_runner;
+pub mod tools;
+pub mod transport;
 pub mod types;
 
+pub use beta::Beta;
 pub use client::{Client, ClientBuilder};
-pub use error::{AnthropicError, ApiError};
+pub use error::{AnthropicError, ApiError, ApiErrorKind};
+pub use options::RequestOptions;
+pub use schemars;
@@ -0,0 +1,79 @@
+//! Types and client methods for the Models API.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use tokio_stream::Stream;
+
+use crate::client::Client;
+use crate::error::AnthropicError;
+use crate::response::Response;
+use crate::types::{ListParams, Page};
+
+/// Metadata describing an available model.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub model_type: String,
+    pub display_name: String,
+    /// RFC 3339 timestamp of the model release.
+    pub created_at: String,
+}
+
+pub type ModelInfoStream<'a> = Pin<Box<dyn Stream<Item = Result<ModelInfo, AnthropicError>> + Send + 'a>>;
+
+impl Client {
+    /// Stream every available model, following `after_id` pagination until `has_more` is false.
+    pub fn list_models(&self) -> ModelInfoStream<'_> {
+        let state = (VecDeque::<ModelInfo>::new(), Some(ListParams::default()));
+
+        let stream = futures_util::stream::unfold(state, move |(mut buffer, mut next)| async move {
+            loop {
+                if let Some(model) = buffer.pop_front() {
+                    return Some((Ok(model), (buffer, next)));
+                }
+
+                let params = next.take()?;
+                match self.list_models_page(&params).await {
+                    Ok(page) => {
+                        if page.has_more {
+                            next = page
+                                .last_id
+                                .or_else(|| page.data.last().map(|model| model.id.clone()))
+                                .map(|after_id| ListParams { after_id: Some(after_id), ..params });
+                        }
+                        buffer.extend(page.data);
+                    }
+                    Err(err) => return Some((Err(err), (buffer, None))),
+                }
+            }
+        });
+
+        Box::pin(stream)
+    }
+
+    /// Fetch a single page of models.
+    pub async fn list_models_page(&self, params: &ListParams) -> Result<Page<ModelInfo>, AnthropicError> {
+        self.list_models_page_with_response(params).await.map(Response::into_body)
+    }
+
+    pub async fn list_models_page_with_response(
+        &self,
+        params: &ListParams,
+    ) -> Result<Response<Page<ModelInfo>>, AnthropicError> {
+        self.get_with_query("/v1/models", params).await
+    }
+
+    /// Retrieve a model by id or alias, e.g. `claude-3-5-sonnet-latest`.
+    ///
+    /// The returned [`ModelInfo::id`] is always the resolved, dated model id.
+    pub async fn get_model(&self, model_id: &str) -> Result<ModelInfo, AnthropicError> {
+        self.get_model_with_response(model_id).await.map(Response::into_body)
+    }
+
+    pub async fn get_model_with_response(&self, model_id: &str) -> Result<Response<ModelInfo>, AnthropicError> {
+        self.get(&format!("/v1/models/{model_id}")).await
+    }
+}
@@ -0,0 +1,113 @@
+//! Per-request overrides applied through [`Client::with_options`](crate::Client::with_options).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+
+use crate::beta::{Beta, BETA_HEADER};
+use crate::error::AnthropicError;
+use crate::retry::RetryPolicy;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Headers, body fields, timeout and retry settings for the requests of a single client view.
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    pub(crate) betas: Vec<Beta>,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) extra_body: serde_json::Map<String, serde_json::Value>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) max_attempts: Option<u32>,
+    pub(crate) retry_policy: Option<Arc<dyn RetryPolicy>>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an `anthropic-beta` flag on top of the ones configured on the client.
+    pub fn beta(mut self, beta: impl Into<Beta>) -> Self {
+        self.betas.push(beta.into());
+        self
+    }
+
+    /// Send an extra header, replacing any default value for the same name.
+    ///
+    /// An `anthropic-beta` header is split into flags and merged like [`RequestOptions::beta`],
+    /// so it adds to the configured and required betas instead of replacing them.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let (name, value) = (name.into(), value.into());
+        if name.eq_ignore_ascii_case(BETA_HEADER) {
+            self.betas.extend(Beta::parse_list(&value));
+        } else {
+            self.headers.push((name, value));
+        }
+        self
+    }
+
+    /// Add a top-level field to JSON request bodies, for parameters the SDK does not model yet.
+    ///
+    /// Extra fields override fields of the same name set by the request type.
+    pub fn extra_body(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.extra_body.insert(key.into(), value.into());
+        self
+    }
+
+    /// Timeout for each attempt, overriding the client timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sent as the `idempotency-key` header, unchanged across retries.
+    pub fn idempotency_key(self, key: impl Into<String>) -> Self {
+        self.header(IDEMPOTENCY_KEY_HEADER, key)
+    }
+
+    /// Limit the number of attempts per request, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Combine with `other`, whose settings take precedence.
+    pub(crate) fn merge(mut self, other: RequestOptions) -> Self {
+        self.betas.extend(other.betas);
+        self.headers.extend(other.headers);
+        self.extra_body.extend(other.extra_body);
+        self.timeout = other.timeout.or(self.timeout);
+        self.max_attempts = other.max_attempts.or(self.max_attempts);
+        self.retry_policy = other.retry_policy.or(self.retry_policy);
+        self
+    }
+
+    pub(crate) fn header_map(&self) -> Result<HeaderMap, AnthropicError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| AnthropicError::InvalidRequest(format!("invalid header name: {name}")))?;
+            headers.insert(name, HeaderValue::from_str(value)?);
+        }
+        Ok(headers)
+    }
+
+    /// `body` as JSON with the extra body fields merged in.
+    pub(crate) fn json_body<I>(&self, body: &I) -> Result<serde_json::Value, AnthropicError>
+    where
+        I: Serialize + ?Sized,
+    {
+        let mut body = serde_json::to_value(body)?;
+        if let Some(object) = body.as_object_mut() {
+            object.extend(self.extra_body.clone());
+        }
+        Ok(body)
+    }
+}
@@ -0,0 +1,203 @@
+//! Tolerant parsing of incomplete JSON produced while a response is streaming.
+
+use std::str::Chars;
+
+use serde_json::{Map, Number, Value};
+
+/// Parse the longest meaningful prefix of a possibly truncated JSON document.
+///
+/// Unterminated strings, arrays and objects are closed, partial literals such as `tru`
+/// are completed and object members whose value has not started yet are dropped.
+/// Returns `None` when no value can be recovered, e.g. for empty input.
+pub fn parse_partial_json(input: &str) -> Option<Value> {
+    Parser { chars: input.chars() }.value()
+}
+
+/// Accumulates JSON fragments, such as `input_json_delta`s, and parses them on demand.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialJson {
+    buffer: String,
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a fragment and return the best-effort value of everything received so far.
+    pub fn push(&mut self, fragment: &str) -> Option<Value> {
+        self.buffer.push_str(fragment);
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<Value> {
+        parse_partial_json(&self.buffer)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+}
+
+struct Parser<'a> {
+    chars: Chars<'a>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.clone().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '{' => Some(self.object()),
+            '[' => Some(self.array()),
+            '"' => self.string().map(|(string, _)| Value::String(string)),
+            't' => self.literal("true", Value::Bool(true)),
+            'f' => self.literal("false", Value::Bool(false)),
+            'n' => self.literal("null", Value::Null),
+            '-' | '0'..='9' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn object(&mut self) -> Value {
+        self.chars.next();
+        let mut object = Map::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.chars.next();
+                }
+                Some('"') => {
+                    let Some((key, true)) = self.string() else {
+                        break;
+                    };
+                    self.skip_whitespace();
+                    if self.peek() != Some(':') {
+                        break;
+                    }
+                    self.chars.next();
+                    match self.value() {
+                        Some(value) => object.insert(key, value),
+                        None => break,
+                    };
+                }
+                Some('}') => {
+                    self.chars.next();
+                    break;
+                }
+                _ => break,
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn array(&mut self) -> Value {
+        self.chars.next();
+        let mut array = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.chars.next();
+                }
+                Some(']') => {
+                    self.chars.next();
+                    break;
+                }
+                Some(_) => match self.value() {
+                    Some(value) => array.push(value),
+                    None => break,
+                },
+                None => break,
+            }
+        }
+        Value::Array(array)
+    }
+
+    /// Parse a string, returning its contents and whether the closing quote was seen.
+    fn string(&mut self) -> Option<(String, bool)> {
+        self.chars.next();
+        let mut string = String::new();
+        while let Some(ch) = self.chars.next() {
+            match ch {
+                '"' => return Some((string, true)),
+                '\\' => match self.chars.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => match self.unicode_escape() {
+                        Some(ch) => string.push(ch),
+                        None => break,
+                    },
+                    _ => break,
+                },
+                ch => string.push(ch),
+            }
+        }
+        Some((string, false))
+    }
+
+    fn unicode_escape(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if self.chars.next()? != '\\' || self.chars.next()? != 'u' {
+            return None;
+        }
+        let low = self.hex4()?;
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low.checked_sub(0xDC00)?))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            code = code * 16 + self.chars.next()?.to_digit(16)?;
+        }
+        Some(code)
+    }
+
+    fn literal(&mut self, literal: &str, value: Value) -> Option<Value> {
+        for expected in literal.chars() {
+            match self.peek() {
+                Some(ch) if ch == expected => {
+                    self.chars.next();
+                }
+                // A truncated literal is unambiguous from its first character.
+                None => return Some(value),
+                Some(_) => return None,
+            }
+        }
+        Some(value)
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let mut number = String::new();
+        while let Some(ch) = self.peek().filter(|ch| matches!(ch, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')) {
+            number.push(ch);
+            self.chars.next();
+        }
+        // Drop dangling signs, exponents and decimal points, e.g. `-`, `1.` or `2e-`.
+        let number = number.trim_end_matches(['-', '+', '.', 'e', 'E']);
+        serde_json::from_str::<Number>(number).ok().map(Value::Number)
+    }
+}
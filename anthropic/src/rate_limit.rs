@@ -0,0 +1,123 @@
+//! Rate limit information reported by the API in response headers.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+
+const RETRY_AFTER_HEADER: &str = "retry-after";
+const REQUESTS_LIMIT_HEADER: &str = "anthropic-ratelimit-requests-limit";
+const REQUESTS_REMAINING_HEADER: &str = "anthropic-ratelimit-requests-remaining";
+const REQUESTS_RESET_HEADER: &str = "anthropic-ratelimit-requests-reset";
+const TOKENS_LIMIT_HEADER: &str = "anthropic-ratelimit-tokens-limit";
+const TOKENS_REMAINING_HEADER: &str = "anthropic-ratelimit-tokens-remaining";
+const TOKENS_RESET_HEADER: &str = "anthropic-ratelimit-tokens-reset";
+
+/// Parsed `retry-after` and `anthropic-ratelimit-*` response headers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub requests_limit: Option<u64>,
+    pub requests_remaining: Option<u64>,
+    pub requests_reset: Option<SystemTime>,
+    pub tokens_limit: Option<u64>,
+    pub tokens_remaining: Option<u64>,
+    pub tokens_reset: Option<SystemTime>,
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    /// Parse rate limit headers, returning `None` when the response carries none of them.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let info = Self {
+            requests_limit: header_u64(headers, REQUESTS_LIMIT_HEADER),
+            requests_remaining: header_u64(headers, REQUESTS_REMAINING_HEADER),
+            requests_reset: header_str(headers, REQUESTS_RESET_HEADER).and_then(parse_rfc3339),
+            tokens_limit: header_u64(headers, TOKENS_LIMIT_HEADER),
+            tokens_remaining: header_u64(headers, TOKENS_REMAINING_HEADER),
+            tokens_reset: header_str(headers, TOKENS_RESET_HEADER).and_then(parse_rfc3339),
+            retry_after: header_str(headers, RETRY_AFTER_HEADER)
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                .map(Duration::from_secs_f64),
+        };
+
+        (info != Self::default()).then_some(info)
+    }
+
+    /// How long to wait before retrying.
+    ///
+    /// Prefers `retry-after`, falling back to the latest reset of any exhausted limit.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        if self.retry_after.is_some() {
+            return self.retry_after;
+        }
+
+        let now = SystemTime::now();
+        [(self.requests_remaining, self.requests_reset), (self.tokens_remaining, self.tokens_reset)]
+            .into_iter()
+            .filter_map(|(remaining, reset)| match remaining {
+                Some(0) => reset,
+                _ => None,
+            })
+            .map(|reset| reset.duration_since(now).unwrap_or_default())
+            .max()
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    header_str(headers, name).and_then(|value| value.trim().parse().ok())
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-06-01T12:30:00Z` or `2024-06-01T12:30:00.5+02:00`.
+fn parse_rfc3339(value: &str) -> Option<SystemTime> {
+    let (date, time) = value.trim().split_once(['T', 't', ' '])?;
+
+    let mut date = date.splitn(3, '-');
+    let year: i64 = date.next()?.parse().ok()?;
+    let month: i64 = date.next()?.parse().ok()?;
+    let day: i64 = date.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset) = match time.strip_suffix(['Z', 'z']) {
+        Some(clock) => (clock, 0),
+        None => {
+            let index = time.rfind(['+', '-'])?;
+            let (clock, offset) = time.split_at(index);
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            (clock, sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60))
+        }
+    };
+
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut clock = clock.splitn(3, ':');
+    let hours: i64 = clock.next()?.parse().ok()?;
+    let minutes: i64 = clock.next()?.parse().ok()?;
+    let seconds: i64 = clock.next()?.parse().ok()?;
+
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        let digits: String = fraction.chars().take(9).collect();
+        digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+    };
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hours * 3600 + minutes * 60 + seconds - offset;
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// Days since the Unix epoch for a proleptic Gregorian calendar date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
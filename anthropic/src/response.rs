@@ -0,0 +1,51 @@
+//! Response bodies together with the HTTP metadata they arrived with.
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+use crate::rate_limit::RateLimitInfo;
+
+const REQUEST_ID_HEADER: &str = "request-id";
+
+/// A parsed response body along with the status, headers and timing of the request.
+///
+/// Returned by the `*_with_response` client methods.
+#[derive(Clone, Debug)]
+pub struct Response<T> {
+    pub body: T,
+    pub status: u16,
+    pub headers: HeaderMap,
+    /// The `request-id` header, to quote when contacting support.
+    pub request_id: Option<String>,
+    /// Number of failed attempts that were retried before this response.
+    pub retries: u32,
+    /// Time from the first attempt until the body was received, including retries.
+    pub elapsed: Duration,
+}
+
+impl<T> Response<T> {
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    /// Rate limit state reported by this response.
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        RateLimitInfo::from_headers(&self.headers)
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Response<U> {
+        Response {
+            body: f(self.body),
+            status: self.status,
+            headers: self.headers,
+            request_id: self.request_id,
+            retries: self.retries,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+pub(crate) fn request_id(headers: &HeaderMap) -> Option<String> {
+    headers.get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
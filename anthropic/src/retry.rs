@@ -0,0 +1,46 @@
+//! Classification of failed requests into retryable and permanent errors.
+
+use std::fmt;
+
+use crate::error::{AnthropicError, ApiErrorKind};
+
+/// Decides whether a failed request attempt should be retried.
+///
+/// `status` is the HTTP status of the response, or `None` when the request failed
+/// before a response was received (connection errors, timeouts).
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    fn should_retry(&self, status: Option<u16>, error: &AnthropicError) -> bool;
+}
+
+/// Retries rate limits, overloads, server errors, timeouts and dropped connections.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRetryPolicy;
+
+impl RetryPolicy for DefaultRetryPolicy {
+    fn should_retry(&self, status: Option<u16>, error: &AnthropicError) -> bool {
+        if let Some(status) = status {
+            if matches!(status, 408 | 409 | 429 | 500 | 502 | 503 | 504 | 529) {
+                return true;
+            }
+        }
+
+        match error {
+            AnthropicError::Http(err) => err.is_connect() || err.is_timeout() || err.is_request() || err.is_body(),
+            AnthropicError::Transport(_) => true,
+            AnthropicError::Api(err) => {
+                matches!(err.error_type, ApiErrorKind::Overloaded | ApiErrorKind::RateLimit | ApiErrorKind::Api)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Never retries.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRetryPolicy;
+
+impl RetryPolicy for NoRetryPolicy {
+    fn should_retry(&self, _status: Option<u16>, _error: &AnthropicError) -> bool {
+        false
+    }
+}
@@ -0,0 +1,281 @@
+//! Accumulation of streaming events into complete messages.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::StreamExt;
+use tokio_stream::Stream;
+
+use crate::client::{Client, MessagesResponseStream};
+use crate::error::AnthropicError;
+use crate::partial_json::{parse_partial_json, PartialJson};
+use crate::types::{ContentBlock, ContentBlockDelta, MessagesRequest, MessagesResponse, MessagesStreamEvent, Usage};
+
+/// Wraps a [`MessagesResponseStream`] and folds its events into a [`MessagesResponse`].
+///
+/// Events are passed through unchanged, so the wrapper can be consumed like the
+/// underlying stream while the accumulated message is available via [`MessageStream::snapshot`].
+/// Tool inputs in the snapshot are updated with a best-effort parse after every delta.
+pub struct MessageStream {
+    inner: MessagesResponseStream,
+    snapshot: Option<MessagesResponse>,
+    partial_json: HashMap<usize, PartialJson>,
+    stopped: bool,
+}
+
+impl MessageStream {
+    pub fn new(inner: MessagesResponseStream) -> Self {
+        Self { inner, snapshot: None, partial_json: HashMap::new(), stopped: false }
+    }
+
+    /// The message accumulated so far, available once `message_start` has been received.
+    pub fn snapshot(&self) -> Option<&MessagesResponse> {
+        self.snapshot.as_ref()
+    }
+
+    /// The `request-id` header of the streaming response.
+    pub fn request_id(&self) -> Option<&str> {
+        self.inner.request_id()
+    }
+
+    /// Best-effort value of the JSON text generated so far, for structured output streams.
+    pub fn partial_output(&self) -> Option<serde_json::Value> {
+        let snapshot = self.snapshot.as_ref()?;
+        let text: String = snapshot
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        parse_partial_json(&text)
+    }
+
+    /// Drive the stream to completion and return the assembled message.
+    ///
+    /// Events that cannot be parsed are skipped, like the stream itself does. Fails with
+    /// [`AnthropicError::IncompleteStream`] if the stream ends before `message_stop`.
+    pub async fn final_message(mut self) -> Result<MessagesResponse, AnthropicError> {
+        while let Some(event) = self.inner.next().await {
+            match event {
+                Ok(event) => self.apply(&event)?,
+                Err(AnthropicError::Deserialize(_)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        match self.snapshot {
+            Some(snapshot) if self.stopped => Ok(snapshot),
+            _ => Err(AnthropicError::IncompleteStream),
+        }
+    }
+
+    fn apply(&mut self, event: &MessagesStreamEvent) -> Result<(), AnthropicError> {
+        if let MessagesStreamEvent::MessageStart { message } = event {
+            self.snapshot = Some(message.clone().into());
+            self.partial_json.clear();
+            self.stopped = false;
+            return Ok(());
+        }
+
+        let Some(snapshot) = self.snapshot.as_mut() else {
+            return Ok(());
+        };
+
+        match event {
+            MessagesStreamEvent::ContentBlockStart { index, content_block } => {
+                if let ContentBlock::ToolUse { .. } | ContentBlock::ServerToolUse { .. } = content_block {
+                    self.partial_json.insert(*index, PartialJson::new());
+                }
+                match snapshot.content.get_mut(*index) {
+                    Some(block) => *block = content_block.clone(),
+                    None => snapshot.content.push(content_block.clone()),
+                }
+            }
+            MessagesStreamEvent::ContentBlockDelta { index, delta } => {
+                match (snapshot.content.get_mut(*index), delta) {
+                    (Some(ContentBlock::Text { text, .. }), ContentBlockDelta::TextDelta { text: delta }) => {
+                        text.push_str(delta)
+                    }
+                    (Some(ContentBlock::Text { citations, .. }), ContentBlockDelta::CitationsDelta { citation }) => {
+                        citations.get_or_insert_with(Vec::new).push(citation.clone())
+                    }
+                    (
+                        Some(ContentBlock::Thinking { thinking, .. }),
+                        ContentBlockDelta::ThinkingDelta { thinking: delta },
+                    ) => thinking.push_str(delta),
+                    (
+                        Some(ContentBlock::Thinking { signature, .. }),
+                        ContentBlockDelta::SignatureDelta { signature: delta },
+                    ) => *signature = Some(delta.clone()),
+                    (
+                        Some(ContentBlock::ToolUse { input, .. } | ContentBlock::ServerToolUse { input, .. }),
+                        ContentBlockDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        if let Some(value) = self.partial_json.entry(*index).or_default().push(partial_json) {
+                            *input = value;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            MessagesStreamEvent::ContentBlockStop { index } => {
+                if let Some(json) = self.partial_json.remove(index) {
+                    if let Some(ContentBlock::ToolUse { input, .. } | ContentBlock::ServerToolUse { input, .. }) =
+                        snapshot.content.get_mut(*index)
+                    {
+                        // Tools without arguments stream no deltas at all.
+                        if !json.as_str().trim().is_empty() {
+                            *input = serde_json::from_str(json.as_str())?;
+                        }
+                    }
+                }
+            }
+            MessagesStreamEvent::MessageDelta { delta, usage } => {
+                snapshot.stop_reason.clone_from(&delta.stop_reason);
+                snapshot.stop_sequence.clone_from(&delta.stop_sequence);
+                snapshot.usage.output_tokens = usage.output_tokens;
+                if let Some(input_tokens) = usage.input_tokens {
+                    snapshot.usage.input_tokens = input_tokens;
+                }
+                if let Some(cache_creation_input_tokens) = usage.cache_creation_input_tokens {
+                    snapshot.usage.cache_creation_input_tokens = cache_creation_input_tokens;
+                }
+                if let Some(cache_read_input_tokens) = usage.cache_read_input_tokens {
+                    snapshot.usage.cache_read_input_tokens = cache_read_input_tokens;
+                }
+            }
+            MessagesStreamEvent::MessageStop => self.stopped = true,
+            MessagesStreamEvent::MessageStart { .. } | MessagesStreamEvent::Unknown(_) => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl From<MessagesResponseStream> for MessageStream {
+    fn from(inner: MessagesResponseStream) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl Stream for MessageStream {
+    type Item = Result<MessagesStreamEvent, AnthropicError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(event))) => Poll::Ready(Some(this.apply(&event).map(|()| event))),
+            other => other,
+        }
+    }
+}
+
+/// A piece of generated text, tagged by the kind of block it belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextChunk {
+    Text(String),
+    Thinking(String),
+}
+
+fn poll_text_chunk(
+    inner: &mut MessageStream,
+    cx: &mut Context<'_>,
+    include_thinking: bool,
+) -> Poll<Option<Result<TextChunk, AnthropicError>>> {
+    loop {
+        match Pin::new(&mut *inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(MessagesStreamEvent::ContentBlockDelta { delta, .. }))) => match delta {
+                ContentBlockDelta::TextDelta { text } => return Poll::Ready(Some(Ok(TextChunk::Text(text)))),
+                ContentBlockDelta::ThinkingDelta { thinking } if include_thinking => {
+                    return Poll::Ready(Some(Ok(TextChunk::Thinking(thinking))))
+                }
+                _ => continue,
+            },
+            // Unparseable events do not end the stream, so they do not interrupt the text either.
+            Poll::Ready(Some(Ok(_) | Err(AnthropicError::Deserialize(_)))) => continue,
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+/// Yields only the text deltas of a streamed message.
+pub struct TextStream {
+    inner: MessageStream,
+}
+
+impl TextStream {
+    pub fn new(inner: MessageStream) -> Self {
+        Self { inner }
+    }
+
+    /// Also yield thinking deltas, tagged separately from text.
+    pub fn with_thinking(self) -> TaggedTextStream {
+        TaggedTextStream { inner: self.inner }
+    }
+
+    /// Usage reported so far; final once the stream has ended.
+    pub fn usage(&self) -> Option<&Usage> {
+        self.inner.snapshot().map(|message| &message.usage)
+    }
+
+    pub fn snapshot(&self) -> Option<&MessagesResponse> {
+        self.inner.snapshot()
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.inner.request_id()
+    }
+}
+
+impl Stream for TextStream {
+    type Item = Result<String, AnthropicError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_text_chunk(&mut self.inner, cx, false).map(|chunk| {
+            chunk.map(|chunk| {
+                chunk.map(|chunk| match chunk {
+                    TextChunk::Text(text) | TextChunk::Thinking(text) => text,
+                })
+            })
+        })
+    }
+}
+
+/// Yields text and thinking deltas of a streamed message as [`TextChunk`]s.
+pub struct TaggedTextStream {
+    inner: MessageStream,
+}
+
+impl TaggedTextStream {
+    /// Usage reported so far; final once the stream has ended.
+    pub fn usage(&self) -> Option<&Usage> {
+        self.inner.snapshot().map(|message| &message.usage)
+    }
+
+    pub fn snapshot(&self) -> Option<&MessagesResponse> {
+        self.inner.snapshot()
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.inner.request_id()
+    }
+}
+
+impl Stream for TaggedTextStream {
+    type Item = Result<TextChunk, AnthropicError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_text_chunk(&mut self.inner, cx, true)
+    }
+}
+
+impl Client {
+    /// Stream only the generated text of a message.
+    pub async fn messages_text_stream(&self, request: MessagesRequest) -> Result<TextStream, AnthropicError> {
+        Ok(TextStream::new(MessageStream::new(self.messages_stream(request).await?)))
+    }
+}
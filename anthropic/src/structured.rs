@@ -0,0 +1,67 @@
+//! Structured output deserialized into Rust types.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use crate::client::Client;
+use crate::error::AnthropicError;
+use crate::tools::json_schema_for;
+use crate::types::{ContentBlock, MessagesRequest, MessagesResponse, OutputConfig, OutputFormat};
+
+/// A response whose text content was deserialized into `T`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedMessage<T> {
+    pub parsed: T,
+    pub response: MessagesResponse,
+}
+
+/// JSON Schema for `T` as accepted by structured outputs, which require closed objects.
+pub fn output_schema_for<T: JsonSchema>() -> serde_json::Value {
+    let mut schema = json_schema_for::<T>();
+    close_objects(&mut schema);
+    schema
+}
+
+fn close_objects(schema: &mut serde_json::Value) {
+    match schema {
+        serde_json::Value::Object(object) => {
+            if object.get("type").is_some_and(|ty| ty == "object") && !object.contains_key("additionalProperties") {
+                object.insert("additionalProperties".into(), serde_json::Value::Bool(false));
+            }
+            object.values_mut().for_each(close_objects);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(close_objects),
+        _ => (),
+    }
+}
+
+impl Client {
+    /// Send a request constrained to the JSON Schema of `T` and deserialize the answer.
+    ///
+    /// Any `output_config.format` on the request is replaced; `effort` is kept.
+    pub async fn messages_parsed<T>(&self, mut request: MessagesRequest) -> Result<ParsedMessage<T>, AnthropicError>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        let format = OutputFormat::JsonSchema { schema: output_schema_for::<T>() };
+        match request.output_config.as_mut() {
+            Some(output_config) => output_config.format = Some(format),
+            None => request.output_config = Some(OutputConfig { format: Some(format), effort: None }),
+        }
+
+        let response = self.messages(request).await?;
+        let raw: String = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        match serde_json::from_str(&raw) {
+            Ok(parsed) => Ok(ParsedMessage { parsed, response }),
+            Err(source) => Err(AnthropicError::StructuredOutput { raw, source }),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Automatic tool-use loop driving [`Client::messages`] with registered handlers.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+
+use crate::client::Client;
+use crate::error::AnthropicError;
+use crate::tools::ToolDefinition;
+use crate::types::{ContentBlock, Message, MessagesRequest, MessagesResponse, Role, StopReason, ToolResultContent};
+
+const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+type ToolFuture = Pin<Box<dyn Future<Output = Result<ToolResultContent, String>> + Send>>;
+type ToolHandler = Arc<dyn Fn(serde_json::Value) -> ToolFuture + Send + Sync>;
+
+/// Runs the tool-use loop: sends the request, executes requested tools and feeds their
+/// results back until the model stops calling tools.
+pub struct ToolRunner {
+    handlers: HashMap<String, ToolHandler>,
+    max_iterations: usize,
+}
+
+impl Default for ToolRunner {
+    fn default() -> Self {
+        Self { handlers: HashMap::new(), max_iterations: DEFAULT_MAX_ITERATIONS }
+    }
+}
+
+impl fmt::Debug for ToolRunner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRunner")
+            .field("tools", &self.handlers.keys().collect::<Vec<_>>())
+            .field("max_iterations", &self.max_iterations)
+            .finish()
+    }
+}
+
+/// Messages exchanged by a [`ToolRunner`] and every response received.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolRunOutput {
+    /// The original messages followed by each assistant turn and tool result turn.
+    pub messages: Vec<Message>,
+    pub responses: Vec<MessagesResponse>,
+    /// The loop hit [`ToolRunner::max_iterations`] while the model was still calling tools.
+    ///
+    /// `messages` then ends with the results of the last tool calls, ready to be sent again.
+    pub stopped_at_limit: bool,
+}
+
+impl ToolRunOutput {
+    /// The response that ended the loop.
+    pub fn final_response(&self) -> Option<&MessagesResponse> {
+        self.responses.last()
+    }
+}
+
+impl ToolRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the async handler invoked for tool calls named `name`.
+    ///
+    /// Handler errors are sent back to the model as tool results with `is_error: true`.
+    pub fn tool<F, Fut, T, E>(mut self, name: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Into<ToolResultContent>,
+        E: fmt::Display,
+    {
+        let handler: ToolHandler = Arc::new(move |input| {
+            let future = handler(input);
+            Box::pin(async move { future.await.map(Into::into).map_err(|err| err.to_string()) })
+        });
+        self.handlers.insert(name.into(), handler);
+        self
+    }
+
+    /// Register a handler for a typed tool, receiving its deserialized input.
+    ///
+    /// Inputs that do not match `T::Input` are reported back to the model as errors.
+    pub fn typed_tool<T, F, Fut, R, E>(self, handler: F) -> Self
+    where
+        T: ToolDefinition,
+        T::Input: Send + 'static,
+        F: Fn(T::Input) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+        R: Into<ToolResultContent>,
+        E: fmt::Display,
+    {
+        let handler = Arc::new(handler);
+        self.tool(T::name(), move |input| {
+            let input = T::parse_input(&input);
+            let handler = Arc::clone(&handler);
+            async move {
+                match input {
+                    Ok(input) => handler(input).await.map_err(|err| err.to_string()),
+                    Err(err) => Err(format!("invalid tool input: {err}")),
+                }
+            }
+        })
+    }
+
+    /// Maximum number of requests sent, 10 by default.
+    ///
+    /// Reaching it is not an error: the transcript so far is returned with
+    /// [`ToolRunOutput::stopped_at_limit`] set.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub async fn run(&self, client: &Client, mut request: MessagesRequest) -> Result<ToolRunOutput, AnthropicError> {
+        let mut responses = Vec::new();
+
+        for _ in 0..self.max_iterations {
+            let response = client.messages(request.clone()).await?;
+            request.messages.push(Message { role: Role::Assistant, content: response.content.clone() });
+
+            let calls: Vec<_> = response
+                .content
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::ToolUse { id, name, input, .. } => Some((id.clone(), name.clone(), input.clone())),
+                    _ => None,
+                })
+                .collect();
+            let done = response.stop_reason != Some(StopReason::ToolUse) || calls.is_empty();
+            responses.push(response);
+            if done {
+                return Ok(ToolRunOutput { messages: request.messages, responses, stopped_at_limit: false });
+            }
+
+            let results = join_all(calls.into_iter().map(|(id, name, input)| self.call(id, name, input))).await;
+            request.messages.push(Message { role: Role::User, content: results });
+        }
+
+        Ok(ToolRunOutput { messages: request.messages, responses, stopped_at_limit: true })
+    }
+
+    async fn call(&self, tool_use_id: String, name: String, input: serde_json::Value) -> ContentBlock {
+        let result = match self.handlers.get(&name) {
+            Some(handler) => handler(input).await,
+            None => Err(format!("unknown tool: {name}")),
+        };
+
+        let (content, is_error) = match result {
+            Ok(content) => (content, None),
+            Err(message) => (ToolResultContent::Text(message), Some(true)),
+        };
+        ContentBlock::ToolResult { tool_use_id, is_error, content, cache_control: None }
+    }
+}
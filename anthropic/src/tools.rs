@@ -0,0 +1,56 @@
+//! Typed tool definitions with JSON Schemas generated from Rust types.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::error::AnthropicError;
+use crate::types::{ContentBlock, Tool};
+
+#[cfg(feature = "macros")]
+pub use anthropic_macros::AnthropicTool;
+
+/// A tool whose input is described by a Rust type.
+///
+/// Implement it by hand or with `#[derive(AnthropicTool)]` (behind the `macros` feature).
+pub trait ToolDefinition {
+    type Input: DeserializeOwned + JsonSchema;
+
+    fn name() -> &'static str;
+
+    fn description() -> &'static str;
+
+    /// JSON Schema of [`ToolDefinition::Input`], sent as the tool's `input_schema`.
+    fn input_schema() -> serde_json::Value {
+        json_schema_for::<Self::Input>()
+    }
+
+    fn tool() -> Tool {
+        Tool::new(Self::name(), Self::description(), Self::input_schema())
+    }
+
+    fn parse_input(input: &serde_json::Value) -> Result<Self::Input, AnthropicError> {
+        Ok(Self::Input::deserialize(input)?)
+    }
+}
+
+/// Generate the JSON Schema for `T` in the form accepted by the API.
+pub fn json_schema_for<T: JsonSchema>() -> serde_json::Value {
+    let mut schema = schemars::SchemaGenerator::default().into_root_schema_for::<T>().to_value();
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("$schema");
+    }
+    schema
+}
+
+impl ContentBlock {
+    /// Deserialize the input of a `tool_use` block calling tool `T`.
+    ///
+    /// Returns `None` for other blocks and for calls to differently named tools.
+    pub fn tool_input<T: ToolDefinition>(&self) -> Option<Result<T::Input, AnthropicError>> {
+        match self {
+            ContentBlock::ToolUse { name, input, .. } if name == T::name() => Some(T::parse_input(input)),
+            _ => None,
+        }
+    }
+}
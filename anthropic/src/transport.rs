@@ -0,0 +1,93 @@
+//! Pluggable HTTP transport used by [`Client`](crate::Client) to send requests.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
+use reqwest::header::HeaderMap;
+
+use crate::client::ByteStream;
+use crate::error::AnthropicError;
+
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<TransportResponse, AnthropicError>> + Send + 'a>>;
+
+/// Sends fully built requests, e.g. through reqwest, hyper or an in-process fake.
+///
+/// Error statuses are returned as responses; the client parses them and decides whether to
+/// retry. Transport failures should be reported as [`AnthropicError::Http`] or
+/// [`AnthropicError::Transport`]. The body of a request may be a stream, as for file uploads.
+pub trait Transport: fmt::Debug + Send + Sync {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_>;
+}
+
+/// Status, headers and streaming body of an HTTP response.
+pub struct TransportResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: ByteStream,
+}
+
+impl TransportResponse {
+    pub fn new(status: u16, headers: HeaderMap, body: ByteStream) -> Self {
+        Self { status, headers, body }
+    }
+
+    /// A response with a fully buffered body.
+    pub fn from_bytes(status: u16, headers: HeaderMap, body: impl Into<Bytes>) -> Self {
+        let body: Result<Bytes, AnthropicError> = Ok(body.into());
+        Self::new(status, headers, Box::pin(futures_util::stream::once(async move { body })))
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Read the whole body.
+    pub async fn bytes(mut self) -> Result<Bytes, AnthropicError> {
+        let mut bytes = BytesMut::new();
+        while let Some(chunk) = self.body.next().await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        Ok(bytes.freeze())
+    }
+
+    pub async fn text(self) -> Result<String, AnthropicError> {
+        let bytes = self.bytes().await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl fmt::Debug for TransportResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransportResponse").field("status", &self.status).field("headers", &self.headers).finish()
+    }
+}
+
+impl From<reqwest::Response> for TransportResponse {
+    fn from(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let body = Box::pin(response.bytes_stream().map(|chunk| chunk.map_err(AnthropicError::Http)));
+        Self::new(status, headers, body)
+    }
+}
+
+/// The default transport, backed by a [`reqwest::Client`].
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+        Box::pin(async move { Ok(self.client.execute(request).await?.into()) })
+    }
+}
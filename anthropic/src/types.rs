@@ -1,8 +1,39 @@
 //! Types for Anthropic's Messages API.
 
+use std::path::Path;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use serde::{Deserialize, Serialize};
 
+use crate::cache::{CacheBreakpoint, CacheStrategy};
 use crate::error::AnthropicError;
+use crate::files::mime_type_for;
+
+/// Implement `Serialize` and `Deserialize` for an internally tagged enum derived with
+/// `#[serde(remote = "Self")]`, keeping values whose `type` is not one of the listed tags in its
+/// `Unknown` variant. Values with a known tag that fail to parse are still reported as errors.
+macro_rules! tagged_with_unknown {
+    ($ty:ident, $($tag:literal),+ $(,)?) => {
+        impl Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    Self::Unknown(value) => value.serialize(serializer),
+                    _ => $ty::serialize(self, serializer),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = serde_json::Value::deserialize(deserializer)?;
+                match value.get("type").and_then(serde_json::Value::as_str) {
+                    Some($($tag)|+) => $ty::deserialize(value).map_err(serde::de::Error::custom),
+                    _ => Ok(Self::Unknown(value)),
+                }
+            }
+        }
+    };
+}
 
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -12,27 +43,54 @@ pub enum Role {
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
-#[serde(rename_all = "snake_case", tag = "type")]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
 pub enum ContentBlock {
     Text {
         text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<Vec<Citation>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     Image {
         source: ImageSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     Document {
         source: DocumentSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<CitationsConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    SearchResult {
+        source: String,
+        title: String,
+        content: Vec<ContentBlock>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<CitationsConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     ToolUse {
         id: String,
         name: String,
         input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     ToolResult {
         tool_use_id: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
         content: ToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     Thinking {
         thinking: String,
@@ -42,26 +100,323 @@ pub enum ContentBlock {
     RedactedThinking {
         data: String,
     },
+    ServerToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    WebSearchToolResult {
+        tool_use_id: String,
+        content: WebSearchToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    WebFetchToolResult {
+        tool_use_id: String,
+        content: WebFetchToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    CodeExecutionToolResult {
+        tool_use_id: String,
+        content: CodeExecutionToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    BashCodeExecutionToolResult {
+        tool_use_id: String,
+        content: BashCodeExecutionToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    TextEditorCodeExecutionToolResult {
+        tool_use_id: String,
+        content: TextEditorCodeExecutionToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    /// A block type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
 }
 
+tagged_with_unknown!(
+    ContentBlock,
+    "text",
+    "image",
+    "document",
+    "search_result",
+    "tool_use",
+    "tool_result",
+    "thinking",
+    "redacted_thinking",
+    "server_tool_use",
+    "web_search_tool_result",
+    "web_fetch_tool_result",
+    "code_execution_tool_result",
+    "bash_code_execution_tool_result",
+    "text_editor_code_execution_tool_result",
+);
+
 impl ContentBlock {
     pub fn text(text: impl Into<String>) -> Self {
-        Self::Text { text: text.into() }
+        Self::Text { text: text.into(), citations: None, cache_control: None }
+    }
+
+    pub fn document(source: DocumentSource) -> Self {
+        Self::Document { source, title: None, context: None, citations: None, cache_control: None }
+    }
+
+    /// A search result block with one text block per passage, for citing retrieved content.
+    pub fn search_result(
+        source: impl Into<String>,
+        title: impl Into<String>,
+        passages: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self::SearchResult {
+            source: source.into(),
+            title: title.into(),
+            content: passages.into_iter().map(Self::text).collect(),
+            citations: None,
+            cache_control: None,
+        }
+    }
+
+    /// Enable citations on a document or search result block; other blocks are returned unchanged.
+    pub fn with_citations(mut self) -> Self {
+        if let Self::Document { citations, .. } | Self::SearchResult { citations, .. } = &mut self {
+            *citations = Some(CitationsConfig { enabled: true });
+        }
+        self
+    }
+
+    /// Citations attached to a text block in a response.
+    pub fn citations(&self) -> &[Citation] {
+        match self {
+            Self::Text { citations: Some(citations), .. } => citations,
+            _ => &[],
+        }
+    }
+
+    /// Mark this block as a prompt cache breakpoint.
+    ///
+    /// Thinking blocks cannot be cached directly and are returned unchanged.
+    pub fn with_cache_control(mut self, ttl: Ttl) -> Self {
+        if let Some(cache_control) = self.cache_control_mut() {
+            *cache_control = Some(CacheControl::ephemeral(ttl));
+        }
+        self
+    }
+
+    pub fn cache_control(&self) -> Option<&CacheControl> {
+        match self {
+            Self::Text { cache_control, .. }
+            | Self::Image { cache_control, .. }
+            | Self::Document { cache_control, .. }
+            | Self::SearchResult { cache_control, .. }
+            | Self::ToolUse { cache_control, .. }
+            | Self::ToolResult { cache_control, .. }
+            | Self::ServerToolUse { cache_control, .. }
+            | Self::WebSearchToolResult { cache_control, .. }
+            | Self::WebFetchToolResult { cache_control, .. }
+            | Self::CodeExecutionToolResult { cache_control, .. }
+            | Self::BashCodeExecutionToolResult { cache_control, .. }
+            | Self::TextEditorCodeExecutionToolResult { cache_control, .. } => cache_control.as_ref(),
+            Self::Thinking { .. } | Self::RedactedThinking { .. } | Self::Unknown(_) => None,
+        }
+    }
+
+    /// Blocks nested in a tool result, search result or content document.
+    pub(crate) fn nested_content(&self) -> &[ContentBlock] {
+        match self {
+            Self::Document { source: DocumentSource::Content { content }, .. }
+            | Self::SearchResult { content, .. }
+            | Self::ToolResult { content: ToolResultContent::Blocks(content), .. } => content,
+            _ => &[],
+        }
+    }
+
+    pub(crate) fn cache_control_mut(&mut self) -> Option<&mut Option<CacheControl>> {
+        match self {
+            Self::Text { cache_control, .. }
+            | Self::Image { cache_control, .. }
+            | Self::Document { cache_control, .. }
+            | Self::SearchResult { cache_control, .. }
+            | Self::ToolUse { cache_control, .. }
+            | Self::ToolResult { cache_control, .. }
+            | Self::ServerToolUse { cache_control, .. }
+            | Self::WebSearchToolResult { cache_control, .. }
+            | Self::WebFetchToolResult { cache_control, .. }
+            | Self::CodeExecutionToolResult { cache_control, .. }
+            | Self::BashCodeExecutionToolResult { cache_control, .. }
+            | Self::TextEditorCodeExecutionToolResult { cache_control, .. } => Some(cache_control),
+            Self::Thinking { .. } | Self::RedactedThinking { .. } | Self::Unknown(_) => None,
+        }
     }
 }
 
+/// Prompt caching breakpoint attached to a content block, system block or tool.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum CacheControl {
+    Ephemeral {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ttl: Option<Ttl>,
+    },
+}
+
+impl CacheControl {
+    pub fn ephemeral(ttl: Ttl) -> Self {
+        Self::Ephemeral { ttl: Some(ttl) }
+    }
+
+    pub fn ttl(&self) -> Ttl {
+        match self {
+            Self::Ephemeral { ttl } => ttl.unwrap_or_default(),
+        }
+    }
+}
+
+/// Lifetime of a prompt cache entry.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum Ttl {
+    #[default]
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ImageSource {
     Base64 { media_type: String, data: String },
     Url { url: String },
+    File { file_id: String },
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum DocumentSource {
     Base64 { media_type: String, data: String },
+    Text { media_type: String, data: String },
+    Content { content: Vec<ContentBlock> },
     Url { url: String },
+    File { file_id: String },
+}
+
+impl DocumentSource {
+    /// A plain text document.
+    pub fn text(data: impl Into<String>) -> Self {
+        Self::Text { media_type: "text/plain".into(), data: data.into() }
+    }
+
+    /// A document made of custom content blocks, cited per block.
+    pub fn content(content: Vec<ContentBlock>) -> Self {
+        Self::Content { content }
+    }
+
+    /// Read a document from disk: PDFs are sent as base64 and anything else as plain text.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, AnthropicError> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|err| AnthropicError::InvalidRequest(format!("cannot read {}: {err}", path.display())))?;
+
+        if mime_type_for(path) == "application/pdf" {
+            return Ok(Self::Base64 { media_type: "application/pdf".into(), data: BASE64_STANDARD.encode(bytes) });
+        }
+        let data = String::from_utf8(bytes).map_err(|_| {
+            AnthropicError::InvalidRequest(format!("{} is neither a PDF nor UTF-8 text", path.display()))
+        })?;
+        Ok(Self::text(data))
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct CitationsConfig {
+    pub enabled: bool,
+}
+
+/// Location in a source document that supports a span of response text.
+///
+/// Document indices count document blocks across the whole request. End indices are exclusive.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
+pub enum Citation {
+    CharLocation {
+        cited_text: String,
+        document_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document_title: Option<String>,
+        start_char_index: usize,
+        end_char_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+    },
+    PageLocation {
+        cited_text: String,
+        document_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document_title: Option<String>,
+        start_page_number: usize,
+        end_page_number: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+    },
+    ContentBlockLocation {
+        cited_text: String,
+        document_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document_title: Option<String>,
+        start_block_index: usize,
+        end_block_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+    },
+    SearchResultLocation {
+        cited_text: String,
+        search_result_index: usize,
+        source: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        start_block_index: usize,
+        end_block_index: usize,
+    },
+    WebSearchResultLocation {
+        cited_text: String,
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        encrypted_index: String,
+    },
+    /// A citation type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(
+    Citation,
+    "char_location",
+    "page_location",
+    "content_block_location",
+    "search_result_location",
+    "web_search_result_location",
+);
+
+impl Citation {
+    pub fn cited_text(&self) -> &str {
+        match self {
+            Self::CharLocation { cited_text, .. }
+            | Self::PageLocation { cited_text, .. }
+            | Self::ContentBlockLocation { cited_text, .. }
+            | Self::SearchResultLocation { cited_text, .. }
+            | Self::WebSearchResultLocation { cited_text, .. } => cited_text,
+            Self::Unknown(value) => value.get("cited_text").and_then(serde_json::Value::as_str).unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -71,6 +426,24 @@ pub enum ToolResultContent {
     Blocks(Vec<ContentBlock>),
 }
 
+impl From<String> for ToolResultContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for ToolResultContent {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ContentBlock>> for ToolResultContent {
+    fn from(blocks: Vec<ContentBlock>) -> Self {
+        Self::Blocks(blocks)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct Message {
     pub role: Role,
@@ -84,6 +457,20 @@ pub enum SystemPrompt {
     Blocks(Vec<ContentBlock>),
 }
 
+impl SystemPrompt {
+    /// Convert to block form with a cache breakpoint on the last block.
+    pub fn with_cache_control(self, ttl: Ttl) -> Self {
+        let mut blocks = match self {
+            Self::Text(text) => vec![ContentBlock::text(text)],
+            Self::Blocks(blocks) => blocks,
+        };
+        if let Some(last) = blocks.pop() {
+            blocks.push(last.with_cache_control(ttl));
+        }
+        Self::Blocks(blocks)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -95,8 +482,495 @@ pub struct Tool {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Tool {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, input_schema: serde_json::Value) -> Self {
+        Self { name: name.into(), description: description.into(), input_schema, cache_control: None }
+    }
+
+    /// Mark this tool as a prompt cache breakpoint, caching all tool definitions up to it.
+    pub fn with_cache_control(mut self, ttl: Ttl) -> Self {
+        self.cache_control = Some(CacheControl::ephemeral(ttl));
+        self
+    }
+}
+
+/// A tool made available to the model: a custom tool, an Anthropic-defined client tool or a
+/// server tool run by Anthropic.
+///
+/// Named `ToolUnion` to stay distinct from the [`ToolDefinition`](crate::tools::ToolDefinition) trait.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ToolUnion {
+    Server(ServerTool),
+    Client(ClientTool),
+    Custom(Tool),
+}
+
+impl ToolUnion {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Server(tool) => tool.name(),
+            Self::Client(tool) => tool.name(),
+            Self::Custom(tool) => &tool.name,
+        }
+    }
+
+    pub fn cache_control(&self) -> Option<&CacheControl> {
+        match self {
+            Self::Server(tool) => tool.cache_control(),
+            Self::Client(tool) => tool.cache_control(),
+            Self::Custom(tool) => tool.cache_control.as_ref(),
+        }
+    }
+
+    pub(crate) fn cache_control_mut(&mut self) -> &mut Option<CacheControl> {
+        match self {
+            Self::Server(tool) => tool.cache_control_mut(),
+            Self::Client(tool) => tool.cache_control_mut(),
+            Self::Custom(tool) => &mut tool.cache_control,
+        }
+    }
+}
+
+impl From<Tool> for ToolUnion {
+    fn from(tool: Tool) -> Self {
+        Self::Custom(tool)
+    }
+}
+
+impl From<ServerTool> for ToolUnion {
+    fn from(tool: ServerTool) -> Self {
+        Self::Server(tool)
+    }
+}
+
+impl From<ClientTool> for ToolUnion {
+    fn from(tool: ClientTool) -> Self {
+        Self::Client(tool)
+    }
+}
+
+/// Tools executed by Anthropic, tagged with their versioned `type`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum ServerTool {
+    #[serde(rename = "web_search_20250305")]
+    WebSearch20250305(WebSearchTool),
+    #[serde(rename = "web_fetch_20250910")]
+    WebFetch20250910(WebFetchTool),
+    #[serde(rename = "code_execution_20250522")]
+    CodeExecution20250522(CodeExecutionTool),
+    #[serde(rename = "code_execution_20250825")]
+    CodeExecution20250825(CodeExecutionTool),
+}
+
+impl ServerTool {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::WebSearch20250305(tool) => &tool.name,
+            Self::WebFetch20250910(tool) => &tool.name,
+            Self::CodeExecution20250522(tool) | Self::CodeExecution20250825(tool) => &tool.name,
+        }
+    }
+
+    pub fn cache_control(&self) -> Option<&CacheControl> {
+        match self {
+            Self::WebSearch20250305(tool) => tool.cache_control.as_ref(),
+            Self::WebFetch20250910(tool) => tool.cache_control.as_ref(),
+            Self::CodeExecution20250522(tool) | Self::CodeExecution20250825(tool) => tool.cache_control.as_ref(),
+        }
+    }
+
+    fn cache_control_mut(&mut self) -> &mut Option<CacheControl> {
+        match self {
+            Self::WebSearch20250305(tool) => &mut tool.cache_control,
+            Self::WebFetch20250910(tool) => &mut tool.cache_control,
+            Self::CodeExecution20250522(tool) | Self::CodeExecution20250825(tool) => &mut tool.cache_control,
+        }
+    }
+
+    pub fn web_search() -> Self {
+        Self::WebSearch20250305(WebSearchTool::default())
+    }
+
+    pub fn web_fetch() -> Self {
+        Self::WebFetch20250910(WebFetchTool::default())
+    }
+
+    pub fn code_execution() -> Self {
+        Self::CodeExecution20250825(CodeExecutionTool::default())
+    }
+}
+
+/// Anthropic-defined tools whose schema is built into the model but which run on the client.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum ClientTool {
+    #[serde(rename = "bash_20250124")]
+    Bash20250124(BashTool),
+    #[serde(rename = "text_editor_20250124")]
+    TextEditor20250124(TextEditorTool),
+    #[serde(rename = "text_editor_20250429")]
+    TextEditor20250429(TextEditorTool),
+    #[serde(rename = "text_editor_20250728")]
+    TextEditor20250728(TextEditorTool),
+    #[serde(rename = "computer_20241022")]
+    Computer20241022(ComputerTool),
+    #[serde(rename = "computer_20250124")]
+    Computer20250124(ComputerTool),
+}
+
+impl ClientTool {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Bash20250124(tool) => &tool.name,
+            Self::TextEditor20250124(tool) | Self::TextEditor20250429(tool) | Self::TextEditor20250728(tool) => {
+                &tool.name
+            }
+            Self::Computer20241022(tool) | Self::Computer20250124(tool) => &tool.name,
+        }
+    }
+
+    pub fn cache_control(&self) -> Option<&CacheControl> {
+        match self {
+            Self::Bash20250124(tool) => tool.cache_control.as_ref(),
+            Self::TextEditor20250124(tool) | Self::TextEditor20250429(tool) | Self::TextEditor20250728(tool) => {
+                tool.cache_control.as_ref()
+            }
+            Self::Computer20241022(tool) | Self::Computer20250124(tool) => tool.cache_control.as_ref(),
+        }
+    }
+
+    fn cache_control_mut(&mut self) -> &mut Option<CacheControl> {
+        match self {
+            Self::Bash20250124(tool) => &mut tool.cache_control,
+            Self::TextEditor20250124(tool) | Self::TextEditor20250429(tool) | Self::TextEditor20250728(tool) => {
+                &mut tool.cache_control
+            }
+            Self::Computer20241022(tool) | Self::Computer20250124(tool) => &mut tool.cache_control,
+        }
+    }
+
+    pub fn bash() -> Self {
+        Self::Bash20250124(BashTool::default())
+    }
+
+    /// The latest text editor, named `str_replace_based_edit_tool`.
+    pub fn text_editor() -> Self {
+        Self::TextEditor20250728(TextEditorTool::default())
+    }
+
+    pub fn computer(display_width_px: u32, display_height_px: u32) -> Self {
+        Self::Computer20250124(ComputerTool::new(display_width_px, display_height_px))
+    }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct BashTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Default for BashTool {
+    fn default() -> Self {
+        Self { name: "bash".into(), cache_control: None }
+    }
+}
+
+/// The text editor tool; `text_editor_20250124` must be named `str_replace_editor`,
+/// later versions `str_replace_based_edit_tool`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TextEditorTool {
+    pub name: String,
+    /// Truncate viewed files to this many characters, `text_editor_20250728` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_characters: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Default for TextEditorTool {
+    fn default() -> Self {
+        Self { name: "str_replace_based_edit_tool".into(), max_characters: None, cache_control: None }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ComputerTool {
+    pub name: String,
+    pub display_width_px: u32,
+    pub display_height_px: u32,
+    /// X11 display number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl ComputerTool {
+    pub fn new(display_width_px: u32, display_height_px: u32) -> Self {
+        Self { name: "computer".into(), display_width_px, display_height_px, display_number: None, cache_control: None }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WebSearchTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_location: Option<UserLocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Default for WebSearchTool {
+    fn default() -> Self {
+        Self {
+            name: "web_search".into(),
+            max_uses: None,
+            allowed_domains: None,
+            blocked_domains: None,
+            user_location: None,
+            cache_control: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WebFetchTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_content_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self {
+            name: "web_fetch".into(),
+            max_uses: None,
+            allowed_domains: None,
+            blocked_domains: None,
+            max_content_tokens: None,
+            cache_control: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CodeExecutionTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl Default for CodeExecutionTool {
+    fn default() -> Self {
+        Self { name: "code_execution".into(), cache_control: None }
+    }
+}
+
+/// Approximate location used to localize web search results.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum UserLocation {
+    Approximate {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        city: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        region: Option<String>,
+        /// Two letter ISO country code.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        country: Option<String>,
+        /// IANA time zone, e.g. `America/New_York`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
+    },
+}
+
+/// Error codes reported by server tool results.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerToolErrorCode {
+    InvalidToolInput,
+    Unavailable,
+    MaxUsesExceeded,
+    TooManyRequests,
+    QueryTooLong,
+    UrlTooLong,
+    UrlNotAllowed,
+    UrlNotAccessible,
+    UnsupportedContentType,
+    ExecutionTimeExceeded,
+    OutputFileTooLarge,
+    FileNotFound,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum WebSearchToolResultContent {
+    Results(Vec<WebSearchResult>),
+    Error(WebSearchToolResultError),
+    /// Content of a shape this version of the SDK does not know, kept as raw JSON.
+    Unknown(serde_json::Value),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WebSearchResult {
+    #[serde(rename = "type")]
+    pub result_type: String,
+    pub url: String,
+    pub title: String,
+    /// Opaque content that must be passed back unchanged in multi-turn conversations.
+    pub encrypted_content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_age: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WebSearchToolResultError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub error_code: ServerToolErrorCode,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
+pub enum WebFetchToolResultContent {
+    WebFetchResult {
+        url: String,
+        /// The fetched page as a `document` block.
+        content: Box<ContentBlock>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retrieved_at: Option<String>,
+    },
+    WebFetchToolResultError {
+        error_code: ServerToolErrorCode,
+    },
+    /// A result type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(WebFetchToolResultContent, "web_fetch_result", "web_fetch_tool_result_error",);
+
+/// A file produced by code execution, downloadable through the Files API.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CodeExecutionOutput {
+    #[serde(rename = "type")]
+    pub output_type: String,
+    pub file_id: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
+pub enum CodeExecutionToolResultContent {
+    CodeExecutionResult {
+        stdout: String,
+        stderr: String,
+        return_code: i32,
+        #[serde(default)]
+        content: Vec<CodeExecutionOutput>,
+    },
+    CodeExecutionToolResultError {
+        error_code: ServerToolErrorCode,
+    },
+    /// A result type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(CodeExecutionToolResultContent, "code_execution_result", "code_execution_tool_result_error",);
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
+pub enum BashCodeExecutionToolResultContent {
+    BashCodeExecutionResult {
+        stdout: String,
+        stderr: String,
+        return_code: i32,
+        #[serde(default)]
+        content: Vec<CodeExecutionOutput>,
+    },
+    BashCodeExecutionToolResultError {
+        error_code: ServerToolErrorCode,
+    },
+    /// A result type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(
+    BashCodeExecutionToolResultContent,
+    "bash_code_execution_result",
+    "bash_code_execution_tool_result_error",
+);
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
+pub enum TextEditorCodeExecutionToolResultContent {
+    TextEditorCodeExecutionViewResult {
+        file_type: String,
+        content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        num_lines: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_line: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        total_lines: Option<u32>,
+    },
+    TextEditorCodeExecutionCreateResult {
+        is_file_update: bool,
+    },
+    TextEditorCodeExecutionStrReplaceResult {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lines: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        old_start: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        old_lines: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_start: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_lines: Option<u32>,
+    },
+    TextEditorCodeExecutionToolResultError {
+        error_code: ServerToolErrorCode,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
+    },
+    /// A result type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(
+    TextEditorCodeExecutionToolResultContent,
+    "text_editor_code_execution_view_result",
+    "text_editor_code_execution_create_result",
+    "text_editor_code_execution_str_replace_result",
+    "text_editor_code_execution_tool_result_error",
+);
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ToolChoice {
@@ -136,7 +1010,7 @@ pub struct MessagesRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tools: Option<Vec<Tool>>,
+    pub tools: Option<Vec<ToolUnion>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -145,6 +1019,10 @@ pub struct MessagesRequest {
     pub output_config: Option<OutputConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_tier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<Vec<McpServer>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_management: Option<ContextManagement>,
 }
 
 /// Configuration for structured output.
@@ -164,6 +1042,66 @@ pub enum OutputFormat {
     Text,
 }
 
+/// A remote MCP server whose tools are exposed to the model through the MCP connector.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum McpServer {
+    Url {
+        name: String,
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        authorization_token: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_configuration: Option<McpToolConfiguration>,
+    },
+}
+
+impl McpServer {
+    pub fn url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self::Url { name: name.into(), url: url.into(), authorization_token: None, tool_configuration: None }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct McpToolConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+/// Server-side edits that keep long conversations within the context window.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct ContextManagement {
+    pub edits: Vec<ContextEdit>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum ContextEdit {
+    /// Clear the oldest tool results once the prompt grows past `trigger`.
+    #[serde(rename = "clear_tool_uses_20250919")]
+    ClearToolUses20250919 {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trigger: Option<ContextEditThreshold>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keep: Option<ContextEditThreshold>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clear_at_least: Option<ContextEditThreshold>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        exclude_tools: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clear_tool_inputs: Option<bool>,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContextEditThreshold {
+    InputTokens { value: u32 },
+    ToolUses { value: u32 },
+}
+
 /// Configuration for extended thinking / reasoning.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -184,11 +1122,14 @@ pub struct MessagesRequestBuilder {
     top_p: Option<f64>,
     top_k: Option<u32>,
     stream: Option<bool>,
-    tools: Option<Vec<Tool>>,
+    tools: Option<Vec<ToolUnion>>,
     tool_choice: Option<ToolChoice>,
     thinking: Option<ThinkingConfig>,
     output_config: Option<OutputConfig>,
     service_tier: Option<String>,
+    mcp_servers: Option<Vec<McpServer>>,
+    context_management: Option<ContextManagement>,
+    cache_strategy: CacheStrategy,
 }
 
 impl MessagesRequestBuilder {
@@ -246,8 +1187,8 @@ impl MessagesRequestBuilder {
         self
     }
 
-    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
-        self.tools = Some(tools);
+    pub fn tools<T: Into<ToolUnion>>(mut self, tools: impl IntoIterator<Item = T>) -> Self {
+        self.tools = Some(tools.into_iter().map(Into::into).collect());
         self
     }
 
@@ -271,8 +1212,32 @@ impl MessagesRequestBuilder {
         self
     }
 
+    pub fn mcp_servers(mut self, mcp_servers: Vec<McpServer>) -> Self {
+        self.mcp_servers = Some(mcp_servers);
+        self
+    }
+
+    pub fn context_management(mut self, context_management: ContextManagement) -> Self {
+        self.context_management = Some(context_management);
+        self
+    }
+
+    /// Choose how prompt cache breakpoints are placed; see [`CacheStrategy`].
+    pub fn cache_strategy(mut self, cache_strategy: CacheStrategy) -> Self {
+        self.cache_strategy = cache_strategy;
+        self
+    }
+
     pub fn build(self) -> Result<MessagesRequest, AnthropicError> {
-        Ok(MessagesRequest {
+        self.build_with_cache_report().map(|(request, _)| request)
+    }
+
+    /// Build the request and report the cache breakpoints placed by [`CacheStrategy::Auto`].
+    ///
+    /// The report is empty with [`CacheStrategy::Manual`] or when every slot was already taken.
+    pub fn build_with_cache_report(self) -> Result<(MessagesRequest, Vec<CacheBreakpoint>), AnthropicError> {
+        let cache_strategy = self.cache_strategy;
+        let mut request = MessagesRequest {
             model: self.model.ok_or_else(|| AnthropicError::InvalidRequest("model is required".into()))?,
             messages: self.messages.ok_or_else(|| AnthropicError::InvalidRequest("messages is required".into()))?,
             max_tokens: self
@@ -290,17 +1255,77 @@ impl MessagesRequestBuilder {
             thinking: self.thinking,
             output_config: self.output_config,
             service_tier: self.service_tier,
-        })
+            mcp_servers: self.mcp_servers,
+            context_management: self.context_management,
+        };
+
+        let placed = match cache_strategy {
+            CacheStrategy::Manual => {
+                request.validate_cache_breakpoints()?;
+                Vec::new()
+            }
+            CacheStrategy::Auto(ttl) => request.place_cache_breakpoints(ttl)?,
+        };
+        Ok((request, placed))
+    }
+}
+
+/// Request body for the `/v1/messages/count_tokens` endpoint.
+///
+/// Mirrors [`MessagesRequest`] without the generation-only fields such as
+/// `max_tokens`, `temperature` or `stream`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CountTokensRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemPrompt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ToolUnion>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<Vec<McpServer>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_management: Option<ContextManagement>,
+}
+
+impl From<MessagesRequest> for CountTokensRequest {
+    fn from(request: MessagesRequest) -> Self {
+        Self {
+            model: request.model,
+            messages: request.messages,
+            system: request.system,
+            tools: request.tools,
+            tool_choice: request.tool_choice,
+            thinking: request.thinking,
+            mcp_servers: request.mcp_servers,
+            context_management: request.context_management,
+        }
     }
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CountTokensResponse {
+    pub input_tokens: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum StopReason {
     EndTurn,
     MaxTokens,
     StopSequence,
     ToolUse,
+    /// A server tool loop was paused; send the response back as-is to continue it.
+    PauseTurn,
+    Refusal,
+    ModelContextWindowExceeded,
+    /// A stop reason this version of the SDK does not know.
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -339,14 +1364,37 @@ pub struct MessagesResponse {
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
-#[serde(rename_all = "snake_case", tag = "type")]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
 pub enum ContentBlockDelta {
-    TextDelta { text: String },
-    InputJsonDelta { partial_json: String },
-    ThinkingDelta { thinking: String },
-    SignatureDelta { signature: String },
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    CitationsDelta {
+        citation: Citation,
+    },
+    /// A delta type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
 }
 
+tagged_with_unknown!(
+    ContentBlockDelta,
+    "text_delta",
+    "input_json_delta",
+    "thinking_delta",
+    "signature_delta",
+    "citations_delta",
+);
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct MessageDeltaUsage {
     pub output_tokens: u32,
@@ -381,13 +1429,76 @@ pub struct StreamMessage {
     pub usage: Usage,
 }
 
+impl From<StreamMessage> for MessagesResponse {
+    fn from(message: StreamMessage) -> Self {
+        Self {
+            id: message.id,
+            message_type: message.message_type,
+            role: message.role,
+            content: message.content,
+            model: message.model,
+            stop_reason: message.stop_reason,
+            stop_sequence: message.stop_sequence,
+            usage: message.usage,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
-#[serde(rename_all = "snake_case", tag = "type")]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
 pub enum MessagesStreamEvent {
-    MessageStart { message: StreamMessage },
-    ContentBlockStart { index: usize, content_block: ContentBlock },
-    ContentBlockDelta { index: usize, delta: ContentBlockDelta },
-    ContentBlockStop { index: usize },
-    MessageDelta { delta: MessageDelta, usage: MessageDeltaUsage },
+    MessageStart {
+        message: StreamMessage,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        delta: MessageDelta,
+        usage: MessageDeltaUsage,
+    },
     MessageStop,
+    /// An event type this version of the SDK does not know, kept as raw JSON.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+tagged_with_unknown!(
+    MessagesStreamEvent,
+    "message_start",
+    "content_block_start",
+    "content_block_delta",
+    "content_block_stop",
+    "message_delta",
+    "message_stop",
+);
+
+/// Cursor parameters accepted by the paginated list endpoints.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct ListParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// A single page returned by a paginated list endpoint.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub has_more: bool,
+    #[serde(default)]
+    pub first_id: Option<String>,
+    #[serde(default)]
+    pub last_id: Option<String>,
 }
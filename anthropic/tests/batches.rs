@@ -0,0 +1,24 @@
+mod common;
+
+use anthropic::batches::BatchResult;
+use common::{client, FakeTransport, MESSAGE};
+use futures_util::StreamExt;
+
+#[tokio::test]
+async fn batch_results_expose_the_response_headers() {
+    let results = format!(
+        "{{\"custom_id\":\"a\",\"result\":{{\"type\":\"succeeded\",\"message\":{MESSAGE}}}}}\n\
+         {{\"custom_id\":\"b\",\"result\":{{\"type\":\"expired\"}}}}\n"
+    );
+    let transport = FakeTransport::new([(200, "application/binary", results)]);
+    let client = client(&transport);
+
+    let response = client.message_batch_results_with_response("msgbatch_1").await.unwrap();
+    assert_eq!(response.request_id.as_deref(), Some("req_fake"));
+
+    let results: Vec<_> = response.into_body().map(Result::unwrap).collect().await;
+    assert_eq!(results.len(), 2);
+    assert!(matches!(&results[0].result, BatchResult::Succeeded { message } if message.id == "msg_1"));
+    assert!(matches!(results[1].result, BatchResult::Expired));
+    assert_eq!(transport.requests.lock().unwrap()[0].path, "/v1/messages/batches/msgbatch_1/results");
+}
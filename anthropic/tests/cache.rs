@@ -0,0 +1,188 @@
+mod common;
+
+use anthropic::batches::BatchRequest;
+use anthropic::cache::{CacheBreakpoint, CacheStrategy};
+use anthropic::types::{
+    ContentBlock, Message, MessagesRequestBuilder, Role, SystemPrompt, Tool, ToolResultContent, Ttl,
+};
+use anthropic::AnthropicError;
+use common::{client, FakeTransport};
+use serde_json::json;
+
+fn message(role: Role, text: &str) -> Message {
+    Message { role, content: vec![ContentBlock::text(text)] }
+}
+
+fn tool(name: &str) -> Tool {
+    Tool::new(name, "A tool.", json!({"type": "object"}))
+}
+
+fn conversation() -> Vec<Message> {
+    vec![
+        message(Role::User, "one"),
+        message(Role::Assistant, "two"),
+        message(Role::User, "three"),
+        message(Role::Assistant, "four"),
+        message(Role::User, "five"),
+    ]
+}
+
+#[test]
+fn auto_breakpoints_follow_priority_order() {
+    let (request, placed) = MessagesRequestBuilder::new("claude-test", conversation(), 16)
+        .tools([tool("a"), tool("b")])
+        .system(SystemPrompt::Text("Be brief.".into()))
+        .cache_strategy(CacheStrategy::Auto(Ttl::FiveMinutes))
+        .build_with_cache_report()
+        .unwrap();
+
+    assert_eq!(
+        placed,
+        [
+            CacheBreakpoint::Tool { index: 1 },
+            CacheBreakpoint::System { index: 0 },
+            CacheBreakpoint::Message { message: 4, block: 0 },
+            CacheBreakpoint::Message { message: 2, block: 0 },
+        ]
+    );
+    let breakpoints: Vec<_> = request.cache_breakpoints().into_iter().map(|(breakpoint, _)| breakpoint).collect();
+    assert_eq!(breakpoints, [placed[0].clone(), placed[1].clone(), placed[3].clone(), placed[2].clone()]);
+}
+
+#[test]
+fn auto_breakpoints_respect_the_budget_left_by_existing_ones() {
+    let mut messages = conversation();
+    messages[0].content = vec![
+        ContentBlock::text("a").with_cache_control(Ttl::FiveMinutes),
+        ContentBlock::text("b").with_cache_control(Ttl::FiveMinutes),
+    ];
+
+    let (request, placed) = MessagesRequestBuilder::new("claude-test", messages, 16)
+        .tools([tool("a")])
+        .system(SystemPrompt::Text("Be brief.".into()))
+        .cache_strategy(CacheStrategy::Auto(Ttl::FiveMinutes))
+        .build_with_cache_report()
+        .unwrap();
+
+    assert_eq!(placed, [CacheBreakpoint::Tool { index: 0 }, CacheBreakpoint::System { index: 0 }]);
+    assert_eq!(request.cache_breakpoints().len(), 4);
+}
+
+#[test]
+fn nothing_is_placed_when_the_budget_is_used_up() {
+    let mut messages = conversation();
+    messages[0].content = (0..4).map(|_| ContentBlock::text("a").with_cache_control(Ttl::FiveMinutes)).collect();
+
+    let (request, placed) = MessagesRequestBuilder::new("claude-test", messages, 16)
+        .tools([tool("a")])
+        .cache_strategy(CacheStrategy::Auto(Ttl::FiveMinutes))
+        .build_with_cache_report()
+        .unwrap();
+
+    assert!(placed.is_empty());
+    assert_eq!(request.cache_breakpoints().len(), 4);
+}
+
+#[test]
+fn manual_builds_report_no_placements() {
+    let (_, placed) = MessagesRequestBuilder::new("claude-test", conversation(), 16)
+        .tools([tool("a").with_cache_control(Ttl::OneHour)])
+        .build_with_cache_report()
+        .unwrap();
+    assert!(placed.is_empty());
+}
+
+fn tool_result(content: Vec<ContentBlock>) -> ContentBlock {
+    ContentBlock::ToolResult {
+        tool_use_id: "toolu_1".into(),
+        is_error: None,
+        content: ToolResultContent::Blocks(content),
+        cache_control: None,
+    }
+}
+
+#[test]
+fn nested_breakpoints_count_toward_the_limit() {
+    let search_result = ContentBlock::search_result("https://example.com", "Example", ["a passage"])
+        .with_cache_control(Ttl::FiveMinutes);
+    let mut messages = conversation();
+    messages[2].content =
+        vec![tool_result(vec![ContentBlock::text("a").with_cache_control(Ttl::FiveMinutes), search_result])
+            .with_cache_control(Ttl::FiveMinutes)];
+
+    let request = MessagesRequestBuilder::new("claude-test", messages.clone(), 16).build().unwrap();
+    let breakpoints: Vec<_> = request.cache_breakpoints().into_iter().map(|(breakpoint, _)| breakpoint).collect();
+    assert_eq!(
+        breakpoints,
+        [
+            CacheBreakpoint::Nested { message: 2, block: 0, path: vec![0] },
+            CacheBreakpoint::Nested { message: 2, block: 0, path: vec![1] },
+            CacheBreakpoint::Message { message: 2, block: 0 },
+        ]
+    );
+
+    messages[4].content = vec![
+        ContentBlock::text("b").with_cache_control(Ttl::FiveMinutes),
+        ContentBlock::text("c").with_cache_control(Ttl::FiveMinutes),
+    ];
+    let error = MessagesRequestBuilder::new("claude-test", messages, 16).build().unwrap_err();
+    assert!(matches!(error, AnthropicError::InvalidRequest(_)), "{error:?}");
+}
+
+#[test]
+fn too_many_breakpoints_are_rejected() {
+    let mut messages = conversation();
+    messages[0].content = (0..5).map(|_| ContentBlock::text("a").with_cache_control(Ttl::FiveMinutes)).collect();
+
+    let error = MessagesRequestBuilder::new("claude-test", messages, 16).build().unwrap_err();
+    assert!(matches!(error, AnthropicError::InvalidRequest(_)), "{error:?}");
+}
+
+#[test]
+fn one_hour_breakpoints_after_five_minute_ones_are_rejected() {
+    let system = SystemPrompt::Text("Be brief.".into());
+
+    let mut messages = conversation();
+    messages[0].content = vec![ContentBlock::text("one").with_cache_control(Ttl::OneHour)];
+    let error = MessagesRequestBuilder::new("claude-test", messages, 16)
+        .system(system.clone().with_cache_control(Ttl::FiveMinutes))
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, AnthropicError::InvalidRequest(_)), "{error:?}");
+
+    // Auto placement checks the breakpoints it adds against the existing ones too.
+    let error = MessagesRequestBuilder::new("claude-test", conversation(), 16)
+        .system(system.clone().with_cache_control(Ttl::FiveMinutes))
+        .cache_strategy(CacheStrategy::Auto(Ttl::OneHour))
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, AnthropicError::InvalidRequest(_)), "{error:?}");
+
+    // The other way around is fine.
+    let (_, placed) = MessagesRequestBuilder::new("claude-test", conversation(), 16)
+        .system(system.with_cache_control(Ttl::OneHour))
+        .cache_strategy(CacheStrategy::Auto(Ttl::FiveMinutes))
+        .build_with_cache_report()
+        .unwrap();
+    assert_eq!(placed.len(), 2);
+}
+
+#[tokio::test]
+async fn batches_and_token_counts_are_validated_before_sending() {
+    let mut messages = conversation();
+    messages[0].content = (0..5).map(|_| ContentBlock::text("a").with_cache_control(Ttl::FiveMinutes)).collect();
+    let mut request = MessagesRequestBuilder::new("claude-test", conversation(), 16).build().unwrap();
+    request.messages = messages;
+
+    let transport = FakeTransport::new::<&str>([]);
+    let client = client(&transport);
+
+    let error = client.count_tokens(request.clone()).await.unwrap_err();
+    assert!(matches!(error, AnthropicError::InvalidRequest(_)), "{error:?}");
+
+    let batch = vec![BatchRequest { custom_id: "first".into(), params: request }];
+    let error = client.create_message_batch(batch).await.unwrap_err();
+    assert!(matches!(&error, AnthropicError::InvalidRequest(message) if message.starts_with("batch request first:")));
+
+    assert!(transport.requests.lock().unwrap().is_empty());
+}
@@ -0,0 +1,113 @@
+//! Test helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anthropic::transport::{Transport, TransportFuture, TransportResponse};
+use anthropic::types::{ContentBlock, Message, MessagesRequest, MessagesRequestBuilder, Role};
+use anthropic::{AnthropicError, Client, ClientBuilder};
+use backoff::ExponentialBackoff;
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderValue};
+
+/// A request recorded by [`FakeTransport`].
+#[derive(Debug)]
+pub struct Recorded {
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+    pub timeout: Option<Duration>,
+}
+
+impl Recorded {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+}
+
+/// A canned reply of [`FakeTransport`].
+#[derive(Debug)]
+pub enum Reply {
+    Body(u16, &'static str, String),
+    /// A 200 response whose body fails halfway through.
+    BrokenBody,
+}
+
+/// Replies with canned responses in order and records the requests it was sent.
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+    responses: Mutex<VecDeque<Reply>>,
+    pub requests: Mutex<Vec<Recorded>>,
+}
+
+impl FakeTransport {
+    pub fn new<B: Into<String>>(responses: impl IntoIterator<Item = (u16, &'static str, B)>) -> Arc<Self> {
+        Self::replies(
+            responses.into_iter().map(|(status, content_type, body)| Reply::Body(status, content_type, body.into())),
+        )
+    }
+
+    pub fn replies(replies: impl IntoIterator<Item = Reply>) -> Arc<Self> {
+        Arc::new(Self { responses: Mutex::new(replies.into_iter().collect()), ..Default::default() })
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+        let body = request.body().and_then(|body| body.as_bytes()).unwrap_or_default().to_vec();
+        let path = request.url().path().to_string();
+        let (headers, timeout) = (request.headers().clone(), request.timeout().copied());
+        self.requests.lock().unwrap().push(Recorded { path, headers, body, timeout });
+
+        let response = self.responses.lock().unwrap().pop_front();
+        Box::pin(async move {
+            let response = response.ok_or_else(|| AnthropicError::Transport("no response queued".into()))?;
+            let mut headers = HeaderMap::new();
+            headers.insert("request-id", HeaderValue::from_static("req_fake"));
+            match response {
+                Reply::Body(status, content_type, body) => {
+                    headers.insert("content-type", HeaderValue::from_static(content_type));
+                    Ok(TransportResponse::from_bytes(status, headers, body))
+                }
+                Reply::BrokenBody => {
+                    headers.insert("content-type", HeaderValue::from_static("application/json"));
+                    let chunks: [Result<Bytes, AnthropicError>; 2] = [
+                        Ok(Bytes::from_static(b"{\"id\":")),
+                        Err(AnthropicError::Transport("connection reset".into())),
+                    ];
+                    Ok(TransportResponse::new(200, headers, Box::pin(futures_util::stream::iter(chunks))))
+                }
+            }
+        })
+    }
+}
+
+pub const MESSAGE: &str = r#"{"id":"msg_1","type":"message","role":"assistant","model":"claude-test","content":[{"type":"text","text":"Hi!"}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":3,"output_tokens":2}}"#;
+pub const OVERLOADED: &str = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+
+/// A client builder sending through `transport`, for tests that change other settings.
+pub fn builder(transport: &Arc<FakeTransport>) -> ClientBuilder {
+    ClientBuilder::new().api_key("test-key").transport(transport.clone())
+}
+
+/// A client sending through `transport`.
+pub fn client(transport: &Arc<FakeTransport>) -> Client {
+    builder(transport).build().unwrap()
+}
+
+/// A single user turn saying hello.
+pub fn request() -> MessagesRequest {
+    let messages = vec![Message { role: Role::User, content: vec![ContentBlock::text("Hello")] }];
+    MessagesRequestBuilder::new("claude-test", messages, 16).build().unwrap()
+}
+
+pub fn fast_backoff() -> ExponentialBackoff {
+    ExponentialBackoff { initial_interval: Duration::from_millis(1), ..Default::default() }
+}
+
+/// Encode events as a `text/event-stream` body, naming each event after its `type`.
+pub fn sse(events: &[serde_json::Value]) -> String {
+    events.iter().map(|event| format!("event: {}\ndata: {event}\n\n", event["type"].as_str().unwrap())).collect()
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "executors")]
+
+use anthropic::executors::{BashExecutor, TextEditorExecutor};
+use serde_json::json;
+
+#[tokio::test]
+async fn create_view_and_edit() {
+    let dir = tempfile::tempdir().unwrap();
+    let editor = TextEditorExecutor::new(dir.path());
+
+    editor.execute(json!({"command": "create", "path": "src/main.rs", "file_text": "fn main() {\n}\n"})).await.unwrap();
+    let view = editor.execute(json!({"command": "view", "path": "src/main.rs"})).await.unwrap();
+    assert_eq!(view, "     1\tfn main() {\n     2\t}\n");
+
+    editor
+        .execute(json!({"command": "insert", "path": "src/main.rs", "insert_line": 1, "insert_text": "    todo!();"}))
+        .await
+        .unwrap();
+    editor
+        .execute(json!({"command": "str_replace", "path": "src/main.rs", "old_str": "todo!()", "new_str": "run()"}))
+        .await
+        .unwrap();
+    let content = std::fs::read_to_string(dir.path().join("src/main.rs")).unwrap();
+    assert_eq!(content, "fn main() {\n    run();\n}\n");
+
+    let view = editor.execute(json!({"command": "view", "path": "src/main.rs", "view_range": [2, -1]})).await.unwrap();
+    assert_eq!(view, "     2\t    run();\n     3\t}\n");
+
+    let listing = editor.execute(json!({"command": "view", "path": "."})).await.unwrap();
+    assert!(listing.ends_with("src/\nsrc/main.rs"), "{listing}");
+}
+
+#[tokio::test]
+async fn str_replace_requires_a_unique_match() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "one\ntwo\none\n").unwrap();
+    let editor = TextEditorExecutor::new(dir.path());
+
+    let missing = editor.execute(json!({"command": "str_replace", "path": "a.txt", "old_str": "three"})).await;
+    assert!(missing.unwrap_err().contains("did not appear verbatim"));
+
+    let multiple = editor.execute(json!({"command": "str_replace", "path": "a.txt", "old_str": "one"})).await;
+    assert!(multiple.unwrap_err().contains("lines 1, 3"));
+}
+
+#[tokio::test]
+async fn paths_cannot_escape_the_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("root");
+    std::fs::create_dir(&root).unwrap();
+    std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+    let editor = TextEditorExecutor::new(&root);
+
+    for path in ["../secret.txt", "a/../../secret.txt", dir.path().join("secret.txt").to_str().unwrap()] {
+        let result = editor.execute(json!({"command": "view", "path": path})).await;
+        assert!(result.unwrap_err().contains("outside of the allowed directory"), "{path}");
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(dir.path(), root.join("link")).unwrap();
+        let result = editor.execute(json!({"command": "create", "path": "link/evil.txt", "file_text": "evil"})).await;
+        assert!(result.unwrap_err().contains("outside of the allowed directory"));
+        assert!(!dir.path().join("evil.txt").exists());
+
+        // Writing through a dangling link would create its target outside the root.
+        std::os::unix::fs::symlink("../outside.txt", root.join("dangling")).unwrap();
+        let result = editor.execute(json!({"command": "create", "path": "dangling", "file_text": "pwned"})).await;
+        assert!(result.unwrap_err().contains("outside of the allowed directory"));
+        std::os::unix::fs::symlink(dir.path().join("missing"), root.join("dangling_dir")).unwrap();
+        let result =
+            editor.execute(json!({"command": "create", "path": "dangling_dir/evil.txt", "file_text": "pwned"})).await;
+        assert!(result.unwrap_err().contains("outside of the allowed directory"));
+        assert!(!dir.path().join("outside.txt").exists());
+        assert!(!dir.path().join("missing").exists());
+
+        // Dangling links that stay inside the root are fine.
+        std::os::unix::fs::symlink("inside.txt", root.join("inside_link")).unwrap();
+        editor.execute(json!({"command": "create", "path": "inside_link", "file_text": "ok"})).await.unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("inside.txt")).unwrap(), "ok");
+    }
+
+    let absolute = root.join("ok.txt");
+    editor.execute(json!({"command": "create", "path": absolute, "file_text": "ok"})).await.unwrap();
+    assert_eq!(std::fs::read_to_string(absolute).unwrap(), "ok");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn bash_runs_in_the_root_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("hello.txt"), "hello").unwrap();
+    let bash = BashExecutor::new(dir.path());
+
+    assert_eq!(bash.execute(json!({"command": "cat hello.txt"})).await.unwrap(), "hello");
+
+    let failed = bash.execute(json!({"command": "echo oops >&2; exit 3"})).await.unwrap_err();
+    assert_eq!(failed, "oops\n\nexit status: 3");
+
+    let timed_out = BashExecutor::new(dir.path())
+        .timeout(std::time::Duration::from_millis(100))
+        .execute(json!({"command": "sleep 5"}))
+        .await;
+    assert!(timed_out.unwrap_err().contains("timed out"));
+}
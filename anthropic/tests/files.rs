@@ -0,0 +1,59 @@
+mod common;
+
+use anthropic::files::FileUpload;
+use common::{builder, client, fast_backoff, FakeTransport, OVERLOADED};
+use futures_util::StreamExt;
+
+const FILE: &str = r#"{"id":"file_1","type":"file","filename":"notes.txt","mime_type":"text/plain","size_bytes":5,"created_at":"2025-01-01T00:00:00Z"}"#;
+
+#[tokio::test]
+async fn uploads_send_a_single_multipart_content_type() {
+    let transport = FakeTransport::new([(200, "application/json", FILE)]);
+    let client = client(&transport);
+
+    let file = client.upload_file(FileUpload::from_bytes("notes.txt", "text/plain", "hello")).await.unwrap();
+    assert_eq!(file.id, "file_1");
+
+    let requests = transport.requests.lock().unwrap();
+    let content_types: Vec<_> = requests[0].headers.get_all("content-type").iter().collect();
+    assert_eq!(content_types.len(), 1);
+    let boundary = content_types[0].to_str().unwrap().strip_prefix("multipart/form-data; boundary=").unwrap();
+
+    let body = String::from_utf8(requests[0].body.clone()).unwrap();
+    assert_eq!(
+        body,
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"notes.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\nhello\r\n--{boundary}--\r\n"
+        )
+    );
+}
+
+#[tokio::test]
+async fn uploads_from_bytes_are_retried() {
+    let transport = FakeTransport::new([(529, "application/json", OVERLOADED), (200, "application/json", FILE)]);
+    let client = builder(&transport).backoff(fast_backoff()).build().unwrap();
+
+    let response =
+        client.upload_file_with_response(FileUpload::from_bytes("notes.txt", "text/plain", "hello")).await.unwrap();
+    assert_eq!(response.retries, 1);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body, requests[1].body);
+}
+
+#[tokio::test]
+async fn downloads_expose_the_response_headers() {
+    let transport = FakeTransport::new([(200, "text/plain", "hello")]);
+    let client = client(&transport);
+
+    let response = client.download_file_with_response("file_1").await.unwrap();
+    assert_eq!(response.request_id.as_deref(), Some("req_fake"));
+    assert_eq!(response.headers["content-type"], "text/plain");
+
+    let chunks: Vec<_> = response.into_body().collect().await;
+    let bytes: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.unwrap()).collect();
+    assert_eq!(bytes, b"hello");
+    assert_eq!(transport.requests.lock().unwrap()[0].path, "/v1/files/file_1/content");
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "macros")]
+
+use anthropic::tools::{AnthropicTool, ToolDefinition};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Get the current weather
+/// for a location.
+///
+/// Temperatures are in Celsius unless `unit` says otherwise.
+#[derive(Deserialize, JsonSchema, AnthropicTool)]
+#[allow(dead_code)]
+struct GetHTTPWeather {
+    /// City and country, e.g. `Paris, France`.
+    location: String,
+    unit: Option<String>,
+}
+
+/// Look up a user by id.
+#[derive(Deserialize, JsonSchema, AnthropicTool)]
+#[tool(name = "lookup", description = "Find a user.")]
+#[allow(dead_code)]
+struct UserLookupV2 {
+    id: u64,
+}
+
+#[test]
+fn derived_names_are_snake_case_with_acronyms_as_one_word() {
+    assert_eq!(GetHTTPWeather::name(), "get_http_weather");
+    assert_eq!(UserLookupV2::name(), "lookup");
+}
+
+#[test]
+fn derived_descriptions_keep_paragraphs() {
+    assert_eq!(
+        GetHTTPWeather::description(),
+        "Get the current weather for a location.\n\nTemperatures are in Celsius unless `unit` says otherwise."
+    );
+    assert_eq!(UserLookupV2::description(), "Find a user.");
+}
+
+#[test]
+fn derived_input_schemas_describe_the_fields() {
+    let schema = GetHTTPWeather::input_schema();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], json!(["location"]));
+    assert_eq!(schema["properties"]["location"]["type"], "string");
+    assert_eq!(schema["properties"]["location"]["description"], "City and country, e.g. `Paris, France`.");
+    assert!(schema.get("$schema").is_none());
+
+    let tool = UserLookupV2::tool();
+    assert_eq!(tool.name, "lookup");
+    assert_eq!(tool.input_schema["properties"]["id"]["type"], "integer");
+}
@@ -0,0 +1,20 @@
+mod common;
+
+use anthropic::{Beta, RequestOptions};
+use common::{builder, request, FakeTransport, MESSAGE};
+
+#[tokio::test]
+async fn beta_headers_from_options_are_merged_with_configured_betas() {
+    let transport = FakeTransport::new([(200, "application/json", MESSAGE)]);
+    let client = builder(&transport).beta(Beta::FilesApi).build().unwrap();
+    let options = RequestOptions::new()
+        .header("Anthropic-Beta", "custom-1, files-api-2025-04-14,custom-2")
+        .header("x-trace", "1");
+
+    client.with_options(options).messages(request()).await.unwrap();
+
+    let requests = transport.requests.lock().unwrap();
+    let betas: Vec<_> = requests[0].headers.get_all("anthropic-beta").iter().collect();
+    assert_eq!(betas, ["files-api-2025-04-14,custom-1,custom-2"]);
+    assert_eq!(requests[0].headers["x-trace"], "1");
+}
@@ -0,0 +1,70 @@
+use anthropic::partial_json::{parse_partial_json, PartialJson};
+use serde_json::json;
+
+#[test]
+fn empty_and_invalid_input_has_no_value() {
+    assert_eq!(parse_partial_json(""), None);
+    assert_eq!(parse_partial_json("   "), None);
+    assert_eq!(parse_partial_json("}"), None);
+}
+
+#[test]
+fn unterminated_strings_are_closed() {
+    assert_eq!(parse_partial_json(r#""hel"#), Some(json!("hel")));
+    assert_eq!(parse_partial_json(r#"{"city": "San Fr"#), Some(json!({"city": "San Fr"})));
+    // A dangling backslash is dropped along with the rest of the escape.
+    assert_eq!(parse_partial_json(r#""a\"#), Some(json!("a")));
+}
+
+#[test]
+fn unterminated_containers_are_closed() {
+    assert_eq!(parse_partial_json("["), Some(json!([])));
+    assert_eq!(parse_partial_json("{"), Some(json!({})));
+    assert_eq!(parse_partial_json(r#"{"a": [1, 2"#), Some(json!({"a": [1, 2]})));
+    assert_eq!(parse_partial_json(r#"[{"a": {"b": [true,"#), Some(json!([{"a": {"b": [true]}}])));
+}
+
+#[test]
+fn partial_literals_are_completed() {
+    assert_eq!(parse_partial_json("[tr"), Some(json!([true])));
+    assert_eq!(parse_partial_json(r#"{"ok": fa"#), Some(json!({"ok": false})));
+    assert_eq!(parse_partial_json("nu"), Some(json!(null)));
+    assert_eq!(parse_partial_json("[trx]"), Some(json!([])));
+}
+
+#[test]
+fn dangling_signs_and_exponents_are_dropped() {
+    assert_eq!(parse_partial_json("-"), None);
+    assert_eq!(parse_partial_json("[1, -"), Some(json!([1])));
+    assert_eq!(parse_partial_json(r#"{"x": 1."#), Some(json!({"x": 1})));
+    assert_eq!(parse_partial_json("2e"), Some(json!(2)));
+    assert_eq!(parse_partial_json("1.5e+"), Some(json!(1.5)));
+    assert_eq!(parse_partial_json("-12e3"), Some(json!(-12e3)));
+}
+
+#[test]
+fn truncated_unicode_escapes_are_dropped() {
+    assert_eq!(parse_partial_json(r#""caf\u00"#), Some(json!("caf")));
+    assert_eq!(parse_partial_json(r#""café"#), Some(json!("café")));
+    // Half of a surrogate pair, with and without a partial low surrogate.
+    assert_eq!(parse_partial_json(r#""smile \ud83d"#), Some(json!("smile ")));
+    assert_eq!(parse_partial_json(r#""smile \ud83d\ude0"#), Some(json!("smile ")));
+    assert_eq!(parse_partial_json(r#""smile 😀"#), Some(json!("smile 😀")));
+}
+
+#[test]
+fn keys_without_values_are_dropped() {
+    assert_eq!(parse_partial_json(r#"{"a": 1, "b"#), Some(json!({"a": 1})));
+    assert_eq!(parse_partial_json(r#"{"a": 1, "b""#), Some(json!({"a": 1})));
+    assert_eq!(parse_partial_json(r#"{"a": 1, "b":"#), Some(json!({"a": 1})));
+    assert_eq!(parse_partial_json(r#"{"a": 1, "b": "#), Some(json!({"a": 1})));
+}
+
+#[test]
+fn fragments_accumulate() {
+    let mut json = PartialJson::new();
+    assert_eq!(json.push(r#"{"loc"#), Some(json!({})));
+    assert_eq!(json.push(r#"ation": "Par"#), Some(json!({"location": "Par"})));
+    assert_eq!(json.push(r#"is"}"#), Some(json!({"location": "Paris"})));
+    assert_eq!(json.as_str(), r#"{"location": "Paris"}"#);
+}
@@ -0,0 +1,121 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anthropic::rate_limit::RateLimitInfo;
+use reqwest::header::{HeaderMap, HeaderValue};
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    pairs.iter().map(|(name, value)| (name.parse().unwrap(), HeaderValue::from_static(value))).collect()
+}
+
+fn requests_reset(value: &'static str) -> Option<SystemTime> {
+    let headers =
+        headers(&[("anthropic-ratelimit-requests-limit", "50"), ("anthropic-ratelimit-requests-reset", value)]);
+    RateLimitInfo::from_headers(&headers).unwrap().requests_reset
+}
+
+fn at(secs: u64, millis: u64) -> Option<SystemTime> {
+    Some(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_millis(millis))
+}
+
+#[test]
+fn responses_without_rate_limit_headers_have_no_info() {
+    assert_eq!(RateLimitInfo::from_headers(&HeaderMap::new()), None);
+}
+
+#[test]
+fn all_headers_are_parsed() {
+    let info = RateLimitInfo::from_headers(&headers(&[
+        ("retry-after", "1.5"),
+        ("anthropic-ratelimit-requests-limit", "50"),
+        ("anthropic-ratelimit-requests-remaining", "0"),
+        ("anthropic-ratelimit-requests-reset", "2024-06-01T12:30:00Z"),
+        ("anthropic-ratelimit-tokens-limit", "40000"),
+        ("anthropic-ratelimit-tokens-remaining", "39000"),
+        ("anthropic-ratelimit-tokens-reset", "2024-06-01T12:30:05Z"),
+    ]))
+    .unwrap();
+
+    assert_eq!(info.retry_after, Some(Duration::from_millis(1500)));
+    assert_eq!(info.requests_limit, Some(50));
+    assert_eq!(info.requests_remaining, Some(0));
+    assert_eq!(info.requests_reset, at(1_717_245_000, 0));
+    assert_eq!(info.tokens_limit, Some(40_000));
+    assert_eq!(info.tokens_remaining, Some(39_000));
+    assert_eq!(info.tokens_reset, at(1_717_245_005, 0));
+}
+
+#[test]
+fn reset_timestamps_accept_utc_offsets_and_fractions() {
+    assert_eq!(requests_reset("1970-01-01T00:00:00Z"), at(0, 0));
+    assert_eq!(requests_reset("2024-06-01t12:30:00z"), at(1_717_245_000, 0));
+    assert_eq!(requests_reset("2024-06-01T14:30:00+02:00"), at(1_717_245_000, 0));
+    assert_eq!(requests_reset("2024-06-01T07:00:00-05:30"), at(1_717_245_000, 0));
+    assert_eq!(requests_reset("2024-06-01T12:30:00.5Z"), at(1_717_245_000, 500));
+    assert_eq!(
+        requests_reset("2024-06-01T12:30:00.123456789Z"),
+        Some(UNIX_EPOCH + Duration::new(1_717_245_000, 123_456_789))
+    );
+    assert_eq!(requests_reset("2024-02-29T00:00:00Z"), at(1_709_164_800, 0));
+}
+
+#[test]
+fn invalid_or_pre_epoch_timestamps_are_ignored() {
+    assert_eq!(requests_reset("1969-12-31T23:59:59Z"), None);
+    assert_eq!(requests_reset("2024-13-01T00:00:00Z"), None);
+    assert_eq!(requests_reset("2024-06-32T00:00:00Z"), None);
+    assert_eq!(requests_reset("2024-06-01"), None);
+    assert_eq!(requests_reset("2024-06-01T12:30:00"), None);
+    assert_eq!(requests_reset("2024-06-01T12:30Z"), None);
+    assert_eq!(requests_reset("tomorrow"), None);
+}
+
+#[test]
+fn invalid_retry_after_values_are_ignored() {
+    assert_eq!(RateLimitInfo::from_headers(&headers(&[("retry-after", "-1")])), None);
+    assert_eq!(RateLimitInfo::from_headers(&headers(&[("retry-after", "soon")])), None);
+    assert_eq!(RateLimitInfo::from_headers(&headers(&[("retry-after", "inf")])), None);
+}
+
+#[test]
+fn retry_after_takes_priority_over_resets() {
+    let info = RateLimitInfo {
+        retry_after: Some(Duration::from_secs(2)),
+        requests_remaining: Some(0),
+        requests_reset: Some(SystemTime::now() + Duration::from_secs(60)),
+        ..Default::default()
+    };
+    assert_eq!(info.retry_delay(), Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn resets_are_used_only_for_exhausted_limits() {
+    let soon = SystemTime::now() + Duration::from_secs(30);
+    let later = SystemTime::now() + Duration::from_secs(90);
+
+    let info = RateLimitInfo {
+        requests_remaining: Some(3),
+        requests_reset: Some(later),
+        tokens_remaining: Some(0),
+        tokens_reset: Some(soon),
+        ..Default::default()
+    };
+    let delay = info.retry_delay().unwrap();
+    assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30), "{delay:?}");
+
+    let info = RateLimitInfo { requests_remaining: Some(0), tokens_remaining: Some(0), ..info };
+    let delay = info.retry_delay().unwrap();
+    assert!(delay > Duration::from_secs(85) && delay <= Duration::from_secs(90), "{delay:?}");
+
+    let info = RateLimitInfo { requests_remaining: Some(1), tokens_remaining: None, ..info };
+    assert_eq!(info.retry_delay(), None);
+}
+
+#[test]
+fn resets_in_the_past_retry_immediately() {
+    let info = RateLimitInfo {
+        requests_remaining: Some(0),
+        requests_reset: Some(SystemTime::now() - Duration::from_secs(5)),
+        ..Default::default()
+    };
+    assert_eq!(info.retry_delay(), Some(Duration::ZERO));
+}
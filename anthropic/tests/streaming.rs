@@ -0,0 +1,100 @@
+mod common;
+
+use anthropic::streaming::MessageStream;
+use anthropic::types::{ContentBlock, MessagesStreamEvent};
+use anthropic::{AnthropicError, ApiErrorKind};
+use common::{client, request, sse, FakeTransport};
+use futures_util::StreamExt;
+use serde_json::json;
+
+#[tokio::test]
+async fn tool_inputs_are_updated_after_every_delta() {
+    let delta = |partial_json: &str| json!({"type": "content_block_delta", "index": 0, "delta": {"type": "input_json_delta", "partial_json": partial_json}});
+    let events = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "model": "claude-test", "content": [], "stop_reason": null, "stop_sequence": null, "usage": {"input_tokens": 3, "output_tokens": 0}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {}}}),
+        delta(r#"{"location": "Par"#),
+        delta(r#"is", "unit": "cel"#),
+        delta(r#"sius", "days": [1, 2"#),
+        delta("]}"),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use", "stop_sequence": null}, "usage": {"output_tokens": 9}}),
+        json!({"type": "message_stop"}),
+    ]);
+    let transport = FakeTransport::new([(200, "text/event-stream", events)]);
+    let client = client(&transport);
+
+    let mut stream = MessageStream::new(client.messages_stream(request()).await.unwrap());
+
+    let mut inputs = Vec::new();
+    while let Some(event) = stream.next().await {
+        if let MessagesStreamEvent::ContentBlockDelta { .. } = event.unwrap() {
+            let Some(ContentBlock::ToolUse { input, .. }) = stream.snapshot().unwrap().content.first() else {
+                panic!("expected a tool use block");
+            };
+            inputs.push(input.clone());
+        }
+    }
+
+    assert_eq!(
+        inputs,
+        [
+            json!({"location": "Par"}),
+            json!({"location": "Paris", "unit": "cel"}),
+            json!({"location": "Paris", "unit": "celsius", "days": [1, 2]}),
+            json!({"location": "Paris", "unit": "celsius", "days": [1, 2]}),
+        ]
+    );
+}
+
+fn text_stream_with_garbage() -> String {
+    let mut events = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "model": "claude-test", "content": [], "stop_reason": null, "stop_sequence": null, "usage": {"input_tokens": 3, "output_tokens": 0}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hello"}}),
+    ]);
+    events.push_str("event: content_block_delta\ndata: {not json\n\n");
+    events.push_str(&sse(&[
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": ", world"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "end_turn", "stop_sequence": null}, "usage": {"output_tokens": 4}}),
+        json!({"type": "message_stop"}),
+    ]));
+    events
+}
+
+#[tokio::test]
+async fn unparseable_events_do_not_end_accumulation() {
+    let transport = FakeTransport::new([(200, "text/event-stream", text_stream_with_garbage())]);
+    let client = client(&transport);
+
+    let message = MessageStream::new(client.messages_stream(request()).await.unwrap()).final_message().await.unwrap();
+    assert_eq!(message.content, vec![ContentBlock::text("Hello, world")]);
+}
+
+#[tokio::test]
+async fn unparseable_events_do_not_interrupt_text_streams() {
+    let transport = FakeTransport::new([(200, "text/event-stream", text_stream_with_garbage())]);
+    let client = client(&transport);
+
+    let mut stream = client.messages_text_stream(request()).await.unwrap();
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk.unwrap());
+    }
+    assert_eq!(text, "Hello, world");
+}
+
+#[tokio::test]
+async fn error_events_carry_the_request_id() {
+    let events = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "model": "claude-test", "content": [], "stop_reason": null, "stop_sequence": null, "usage": {"input_tokens": 3, "output_tokens": 0}}}),
+        json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+    ]);
+    let transport = FakeTransport::new([(200, "text/event-stream", events)]);
+    let client = client(&transport);
+
+    let error = MessageStream::new(client.messages_stream(request()).await.unwrap()).final_message().await.unwrap_err();
+    assert!(matches!(&error, AnthropicError::Api(error) if error.error_type == ApiErrorKind::Overloaded), "{error:?}");
+    assert_eq!(error.request_id(), Some("req_fake"));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anthropic::tool_runner::ToolRunner;
+use anthropic::types::{ContentBlock, Message, Role, ToolResultContent};
+use common::{client, request, FakeTransport, MESSAGE};
+use serde_json::{json, Value};
+
+fn tool_calls(calls: &[(&str, &str, Value)]) -> String {
+    let content: Vec<_> = calls
+        .iter()
+        .map(|(id, name, input)| json!({"type": "tool_use", "id": id, "name": name, "input": input}))
+        .collect();
+    json!({
+        "id": "msg_tools", "type": "message", "role": "assistant", "model": "claude-test", "content": content,
+        "stop_reason": "tool_use", "stop_sequence": null, "usage": {"input_tokens": 3, "output_tokens": 2}
+    })
+    .to_string()
+}
+
+fn tool_results(message: &Message) -> Vec<(&str, Option<bool>, &ToolResultContent)> {
+    message
+        .content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::ToolResult { tool_use_id, is_error, content, .. } => {
+                (tool_use_id.as_str(), *is_error, content)
+            }
+            other => panic!("expected a tool result, got {other:?}"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn tool_calls_run_concurrently() {
+    let calls = tool_calls(&[("toolu_1", "wait", json!({})), ("toolu_2", "wait", json!({}))]);
+    let transport = FakeTransport::new([(200, "application/json", calls), (200, "application/json", MESSAGE.into())]);
+    let client = client(&transport);
+
+    // Each call waits for the other to start, which only happens if they run at the same time.
+    let started = Arc::new(AtomicUsize::new(0));
+    let runner = ToolRunner::new().tool("wait", move |_| {
+        let started = Arc::clone(&started);
+        async move {
+            started.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..1000 {
+                if started.load(Ordering::SeqCst) == 2 {
+                    return Ok("done");
+                }
+                tokio::task::yield_now().await;
+            }
+            Err("the other call never started")
+        }
+    });
+
+    let output = runner.run(&client, request()).await.unwrap();
+    assert!(!output.stopped_at_limit);
+    assert_eq!(output.responses.len(), 2);
+    assert_eq!(
+        tool_results(&output.messages[2]),
+        [("toolu_1", None, &"done".into()), ("toolu_2", None, &"done".into())]
+    );
+
+    // The tool results are sent back as the last user turn.
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests[1].json()["messages"][2]["content"][1]["tool_use_id"], "toolu_2");
+}
+
+#[tokio::test]
+async fn unknown_tools_and_handler_errors_are_reported_to_the_model() {
+    let calls = tool_calls(&[("toolu_1", "missing", json!({})), ("toolu_2", "fail", json!({"path": "/"}))]);
+    let transport = FakeTransport::new([(200, "application/json", calls), (200, "application/json", MESSAGE.into())]);
+    let client = client(&transport);
+
+    let runner = ToolRunner::new().tool("fail", |input: Value| async move {
+        Err::<String, _>(format!("cannot read {}", input["path"].as_str().unwrap()))
+    });
+
+    let output = runner.run(&client, request()).await.unwrap();
+    assert_eq!(
+        tool_results(&output.messages[2]),
+        [("toolu_1", Some(true), &"unknown tool: missing".into()), ("toolu_2", Some(true), &"cannot read /".into()),]
+    );
+    assert_eq!(output.final_response().unwrap().content, vec![ContentBlock::text("Hi!")]);
+}
+
+#[tokio::test]
+async fn the_transcript_is_returned_at_the_iteration_limit() {
+    let transport = FakeTransport::new([
+        (200, "application/json", tool_calls(&[("toolu_1", "echo", json!({"n": 1}))])),
+        (200, "application/json", tool_calls(&[("toolu_2", "echo", json!({"n": 2}))])),
+    ]);
+    let client = client(&transport);
+
+    let runner = ToolRunner::new()
+        .max_iterations(2)
+        .tool("echo", |input: Value| async move { Ok::<_, String>(input["n"].to_string()) });
+
+    let output = runner.run(&client, request()).await.unwrap();
+    assert!(output.stopped_at_limit);
+    assert_eq!(output.responses.len(), 2);
+    assert_eq!(transport.requests.lock().unwrap().len(), 2);
+
+    // user, assistant, tool results, assistant, tool results
+    assert_eq!(output.messages.len(), 5);
+    assert_eq!(output.messages[4].role, Role::User);
+    assert_eq!(tool_results(&output.messages[4]), [("toolu_2", None, &"2".into())]);
+}
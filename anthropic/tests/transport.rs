@@ -0,0 +1,108 @@
+mod common;
+
+use std::time::Duration;
+
+use anthropic::streaming::MessageStream;
+use anthropic::types::ContentBlock;
+use anthropic::{AnthropicError, RequestOptions};
+use common::{builder, client, fast_backoff, request, FakeTransport, Reply, MESSAGE, OVERLOADED};
+
+#[tokio::test]
+async fn messages_go_through_the_transport() {
+    let transport = FakeTransport::new([(200, "application/json", MESSAGE)]);
+    let client = client(&transport);
+
+    let response = client.messages_with_response(request()).await.unwrap();
+    assert_eq!(response.body.content, vec![ContentBlock::text("Hi!")]);
+    assert_eq!(response.request_id.as_deref(), Some("req_fake"));
+    assert_eq!(response.retries, 0);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests[0].path, "/v1/messages");
+    assert_eq!(requests[0].headers["x-api-key"], "test-key");
+    assert_eq!(requests[0].headers["content-type"], "application/json");
+    assert_eq!(requests[0].json()["model"], "claude-test");
+}
+
+#[tokio::test]
+async fn retries_use_the_transport() {
+    let transport = FakeTransport::new([(529, "application/json", OVERLOADED), (200, "application/json", MESSAGE)]);
+    let client = builder(&transport).backoff(fast_backoff()).build().unwrap();
+
+    let response = client.messages_with_response(request()).await.unwrap();
+    assert_eq!(response.retries, 1);
+    assert_eq!(transport.requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn failures_reading_a_successful_body_are_retried() {
+    let transport =
+        FakeTransport::replies([Reply::BrokenBody, Reply::Body(200, "application/json", MESSAGE.to_string())]);
+    let client = builder(&transport).backoff(fast_backoff()).build().unwrap();
+
+    let response = client.messages_with_response(request()).await.unwrap();
+    assert_eq!(response.body.content, vec![ContentBlock::text("Hi!")]);
+    assert_eq!(response.retries, 1);
+}
+
+#[tokio::test]
+async fn api_errors_are_parsed() {
+    let transport = FakeTransport::new([(529, "application/json", OVERLOADED)]);
+    let client = builder(&transport).max_attempts(1).build().unwrap();
+
+    let error = client.messages(request()).await.unwrap_err();
+    assert_eq!(error.status(), Some(529));
+    assert_eq!(error.request_id(), Some("req_fake"));
+}
+
+const EVENTS: &str = concat!(
+    "event: message_start\n",
+    r#"data: {"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","model":"claude-test","content":[],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":3,"output_tokens":0}}}"#,
+    "\n\nevent: content_block_start\n",
+    r#"data: {"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+    "\n\nevent: content_block_delta\n",
+    r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi!"}}"#,
+    "\n\nevent: message_delta\n",
+    r#"data: {"type":"message_delta","delta":{"stop_reason":"end_turn","stop_sequence":null},"usage":{"output_tokens":2}}"#,
+    "\n\nevent: message_stop\n",
+    r#"data: {"type":"message_stop"}"#,
+    "\n\n",
+);
+
+#[tokio::test]
+async fn streams_are_read_from_the_transport() {
+    let transport = FakeTransport::new([(200, "text/event-stream", EVENTS)]);
+    let client = client(&transport);
+
+    let stream = client.messages_stream(request()).await.unwrap();
+    assert_eq!(stream.request_id(), Some("req_fake"));
+
+    let message = MessageStream::new(stream).final_message().await.unwrap();
+    assert_eq!(message.content, vec![ContentBlock::text("Hi!")]);
+    assert_eq!(message.usage.output_tokens, 2);
+}
+
+#[tokio::test]
+async fn streams_without_message_stop_are_incomplete() {
+    let truncated = &EVENTS[..EVENTS.find("event: message_stop").unwrap()];
+    let transport = FakeTransport::new([(200, "text/event-stream", truncated)]);
+    let client = client(&transport);
+
+    let stream = client.messages_stream(request()).await.unwrap();
+    let error = MessageStream::new(stream).final_message().await.unwrap_err();
+    assert!(matches!(error, AnthropicError::IncompleteStream));
+}
+
+#[tokio::test]
+async fn every_request_carries_a_timeout() {
+    let transport = FakeTransport::new([MESSAGE; 3].map(|body| (200, "application/json", body)));
+    let client = client(&transport);
+    client.messages(request()).await.unwrap();
+
+    let client = builder(&transport).timeout(Duration::from_secs(5)).build().unwrap();
+    client.messages(request()).await.unwrap();
+    client.with_options(RequestOptions::new().timeout(Duration::from_secs(1))).messages(request()).await.unwrap();
+
+    let timeouts: Vec<_> = transport.requests.lock().unwrap().iter().map(|request| request.timeout).collect();
+    assert_eq!(timeouts, [60, 5, 1].map(|secs| Some(Duration::from_secs(secs))));
+}
@@ -0,0 +1,51 @@
+use anthropic::types::{Citation, ContentBlock, MessagesStreamEvent, WebFetchToolResultContent};
+use serde_json::json;
+
+#[test]
+fn unknown_content_blocks_are_kept_and_round_trip() {
+    let raw = json!({"type": "hologram", "frames": 3});
+    let block: ContentBlock = serde_json::from_value(raw.clone()).unwrap();
+    assert_eq!(block, ContentBlock::Unknown(raw.clone()));
+    assert_eq!(serde_json::to_value(&block).unwrap(), raw);
+}
+
+#[test]
+fn known_blocks_that_fail_to_parse_are_errors() {
+    assert!(serde_json::from_value::<ContentBlock>(json!({"type": "text", "text": 42})).is_err());
+    assert!(serde_json::from_value::<ContentBlock>(json!({"type": "tool_use", "id": "toolu_1"})).is_err());
+}
+
+#[test]
+fn unknown_citations_stay_inside_their_text_block() {
+    let citation = json!({"type": "video_location", "cited_text": "hello", "start_frame": 1});
+    let block: ContentBlock =
+        serde_json::from_value(json!({"type": "text", "text": "hi", "citations": [citation.clone()]})).unwrap();
+
+    let ContentBlock::Text { citations: Some(citations), .. } = &block else { panic!("expected a text block") };
+    assert_eq!(citations, &[Citation::Unknown(citation)]);
+    assert_eq!(citations[0].cited_text(), "hello");
+}
+
+#[test]
+fn unknown_nested_results_are_kept() {
+    let content = json!({"type": "web_fetch_pdf_result", "url": "https://example.com"});
+    let block: ContentBlock = serde_json::from_value(
+        json!({"type": "web_fetch_tool_result", "tool_use_id": "srvtoolu_1", "content": content.clone()}),
+    )
+    .unwrap();
+
+    let ContentBlock::WebFetchToolResult { content: parsed, .. } = block else { panic!("expected a web fetch result") };
+    assert_eq!(parsed, WebFetchToolResultContent::Unknown(content));
+}
+
+#[test]
+fn stream_events_fall_back_only_for_unknown_types() {
+    let event: MessagesStreamEvent = serde_json::from_value(json!({"type": "message_pause", "seconds": 1})).unwrap();
+    assert!(matches!(event, MessagesStreamEvent::Unknown(_)));
+
+    let event: MessagesStreamEvent = serde_json::from_value(json!({"type": "message_stop"})).unwrap();
+    assert_eq!(event, MessagesStreamEvent::MessageStop);
+
+    let malformed = json!({"type": "message_delta", "delta": {"stop_reason": 7}});
+    assert!(serde_json::from_value::<MessagesStreamEvent>(malformed).is_err());
+}
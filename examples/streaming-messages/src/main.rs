@@ -1,33 +1,17 @@
 use std::io::Write;
 
+use anthropic::streaming::MessageStream;
 use anthropic::types::{ContentBlock, ContentBlockDelta, Message, MessagesRequestBuilder, MessagesStreamEvent, Role};
 use anthropic::Client;
 use dotenvy::dotenv;
 use tokio_stream::StreamExt;
 
-fn extend_messages(messages: &mut Vec<Message>, event: &MessagesStreamEvent) {
-    match event {
-        MessagesStreamEvent::MessageStart { message } => messages.push(message.clone()),
-        MessagesStreamEvent::ContentBlockStart { content_block, .. } => {
-            messages.last_mut().unwrap().content.push(content_block.clone());
-        }
-        MessagesStreamEvent::ContentBlockDelta { index, delta } => match (
-            messages.last_mut().unwrap().content.get_mut(*index),
-            delta,
-        ) {
-            (Some(ContentBlock::Text { text }), ContentBlockDelta::TextDelta { text: delta }) => *text += delta,
-            _ => (),
-        },
-        _ => (),
-    }
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
     let client = Client::from_env()?;
-    let mut messages = vec![Message {
+    let messages = vec![Message {
         role: Role::User,
         content: vec![ContentBlock::text("Stream a short greeting.")],
     }];
@@ -35,29 +19,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let request = MessagesRequestBuilder::new("claude-3-5-sonnet-20240620", messages.clone(), 128).build()?;
 
     println!("\n\nSending messages:\n{messages:#?}\n");
-    let mut stream = client.messages_stream(request).await?;
+    let mut stream = MessageStream::new(client.messages_stream(request).await?);
 
     while let Some(resp) = stream.next().await {
         match resp {
-            Ok(response) => {
-                extend_messages(&mut messages, &response);
-
-                if let MessagesStreamEvent::ContentBlockDelta {
-                    delta: ContentBlockDelta::TextDelta { text },
-                    ..
-                } = response
-                {
-                    print!("{text}");
-                    std::io::stdout().flush().ok();
-                }
+            Ok(MessagesStreamEvent::ContentBlockDelta {
+                delta: ContentBlockDelta::TextDelta { text },
+                ..
+            }) => {
+                print!("{text}");
+                std::io::stdout().flush().ok();
             }
+            Ok(_) => (),
             Err(e) => {
                 println!("\n{e}\n");
             }
         }
     }
 
-    println!("\n\nNew messages:\n{messages:#?}");
+    println!("\n\nFinal message:\n{:#?}", stream.snapshot());
 
     Ok(())
 }